@@ -0,0 +1,135 @@
+//! [`Arbitrary`] implementations, for fuzzing
+//!
+//! Sizes are spread log-uniformly: every scale, from bytes up to the largest unit
+//! the backing integer can hold, is about as likely as any other.
+//! [`ValidInput`] and [`InvalidInput`] are human readable sizes that parse, and fail to.
+//! Valid inputs go up to thousands of yottabytes, so only a `ByteSize<u128>` holds them all.
+//!
+//! ```
+//! use arbitrary::{Arbitrary, Unstructured};
+//! use xbytes::{arbitrary::InvalidInput, ByteSize};
+//!
+//! let mut u = Unstructured::new(&[7, 42, 3, 250, 1, 9]);
+//! let size = ByteSize::<u64>::arbitrary(&mut u).unwrap();
+//! assert_eq!(Ok(size), size.to_string_exact().parse());
+//!
+//! let InvalidInput(input) = InvalidInput::arbitrary(&mut u).unwrap();
+//! assert!(input.parse::<ByteSize>().is_err());
+//! ```
+
+use super::{sample, Backing, BitSize, ByteSize, Format, Int, Mode, SizeVariant, Unit, UnitPrefix};
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+fn scaled(u: &mut Unstructured<'_>, per_unit: fn(&Unit) -> Int, max: Int) -> Result<Int> {
+    let scale = *u.choose(&sample::scales(per_unit, max))?;
+    Ok(sample::scaled(
+        scale,
+        u.int_in_range(0..=1023)?,
+        u.arbitrary()?,
+        max,
+    ))
+}
+
+impl<'a, T: Backing> Arbitrary<'a> for ByteSize<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let bytes = scaled(u, |unit| unit.effective_value() / 8, T::MAX.into())?;
+        Ok(ByteSize(T::saturating_from(bytes)))
+    }
+}
+
+impl<'a, T: Backing> Arbitrary<'a> for BitSize<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let bits = scaled(u, Unit::effective_value, T::MAX.into())?;
+        Ok(BitSize(T::saturating_from(bits)))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Unit {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(&super::sizes::ALL).copied()
+    }
+}
+
+impl<'a> Arbitrary<'a> for UnitPrefix {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(&UnitPrefix::ALL).copied()
+    }
+}
+
+impl<'a> Arbitrary<'a> for SizeVariant {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(&[SizeVariant::Bit, SizeVariant::Byte]).copied()
+    }
+}
+
+impl<'a> Arbitrary<'a> for Mode {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Mode::from_bits_truncate(u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Format {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Format::from_bits_truncate(u.arbitrary()?))
+    }
+}
+
+/// A human readable size that parses into a `ByteSize<u128>`, like `"1,234.5 KiloBytes"`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidInput(pub String);
+
+impl<'a> Arbitrary<'a> for ValidInput {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(ValidInput(sample::valid_input(
+            u.int_in_range(0..=9_999_999)?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+            u.int_in_range(0..=2)?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+        )))
+    }
+}
+
+/// A human readable size that fails to parse, like `"1,23 KiB"`
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvalidInput(pub String);
+
+impl<'a> Arbitrary<'a> for InvalidInput {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(InvalidInput(sample::invalid_input(
+            u.arbitrary()?,
+            u.int_in_range(0..=9_999_999)?,
+            u.arbitrary()?,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::sizes::YOBI_BYTE, *};
+
+    #[test]
+    fn inputs() {
+        let data = (0..=255).cycle().take(4096).collect::<Vec<u8>>();
+        let mut u = Unstructured::new(&data);
+        for _ in 0..64 {
+            let ValidInput(input) = u.arbitrary().unwrap();
+            assert!(input.parse::<ByteSize<u128>>().is_ok(), "{:?}", input);
+            let InvalidInput(input) = u.arbitrary().unwrap();
+            assert!(input.parse::<ByteSize>().is_err(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn sizes() {
+        let data = (0..=255).rev().cycle().take(4096).collect::<Vec<u8>>();
+        let mut u = Unstructured::new(&data);
+        for _ in 0..64 {
+            let size = ByteSize::<u32>::arbitrary(&mut u).unwrap();
+            assert!(size <= ByteSize(u32::MAX));
+            let size = BitSize::<u128>::arbitrary(&mut u).unwrap();
+            assert!(size <= BitSize::of_u128(1024, YOBI_BYTE));
+        }
+    }
+}
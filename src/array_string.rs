@@ -0,0 +1,157 @@
+use core::{fmt, ops::Deref, str};
+
+/// A string of at most `N` bytes, stored inline
+///
+/// Holds formatted sizes without touching the heap, see [`ByteSizeRepr::to_array_string`].
+/// Any `write_str` that doesn't fit fails with [`fmt::Error`], without writing any of it.
+///
+/// [`ByteSizeRepr::to_array_string`]: super::ByteSizeRepr::to_array_string
+#[derive(Copy, Clone)]
+pub struct ArrayString<const N: usize = 64> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> ArrayString<N> {
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // only ever written to from whole `&str`s
+        str::from_utf8(&self.buf[..self.len]).expect("unexpected non-utf8 bytes in buffer")
+    }
+
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> Default for ArrayString<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> fmt::Write for ArrayString<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.buf
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+impl<const N: usize> Deref for ArrayString<N> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for ArrayString<N> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> fmt::Display for ArrayString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl<const N: usize> fmt::Debug for ArrayString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize, const M: usize> PartialEq<ArrayString<M>> for ArrayString<N> {
+    fn eq(&self, other: &ArrayString<M>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for ArrayString<N> {}
+
+impl<const N: usize> PartialEq<str> for ArrayString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for ArrayString<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> PartialEq<ArrayString<N>> for str {
+    fn eq(&self, other: &ArrayString<N>) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl<const N: usize> PartialEq<ArrayString<N>> for &str {
+    fn eq(&self, other: &ArrayString<N>) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl<const N: usize> core::hash::Hash for ArrayString<N> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+
+    #[test]
+    fn write() {
+        let mut s = ArrayString::<8>::new();
+        assert!(s.is_empty());
+        write!(s, "{}-ab", 12).unwrap();
+        assert_eq!("12-ab", s);
+        assert_eq!(5, s.len());
+
+        // all or nothing
+        assert_eq!(Err(fmt::Error), s.write_str("cdef"));
+        assert_eq!("12-ab", s);
+        s.write_str("cde").unwrap();
+        assert_eq!("12-abcde", s);
+
+        s.clear();
+        assert_eq!("", s);
+        assert_eq!(8, s.capacity());
+    }
+}
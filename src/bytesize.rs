@@ -1,8 +1,10 @@
-use super::{sizes, Float, Int, ParseError, Unit};
-use std::{convert::TryInto, fmt, str::FromStr};
+use super::{sizes, ArrayString, Float, Int, ParseError, SizeVariant, Unit};
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+use core::{convert::TryInto, fmt, fmt::Write, str::FromStr};
 
 mod flags {
-    #![allow(non_upper_case_globals)]
+    #![allow(non_upper_case_globals, clippy::identity_op)]
 
     use bitflags::bitflags;
 
@@ -40,6 +42,9 @@ mod flags {
 
             const ShowThousandsSeparator = 1 << 10; // 1 B, 2.13 KB, 1,024.43 MiB
             const NoSpace                = 1 << 11; // 1B, 2.13KB, 1024.43MiB
+
+            // (only for whole values, always spaced from the unit)
+            const Hex                    = 1 << 12; // 0x1 B, 2.13 KB, 0x400 MiB
         }
     }
 }
@@ -51,16 +56,16 @@ pub struct ReprFormat {
     flags: Format,
     n_spaces: usize,
     precision: usize,
-    thousands_separator: &'static str,
+    thousands_separator: char,
 }
 
 impl ReprFormat {
-    const fn default() -> Self {
+    pub(crate) const fn default() -> Self {
         Self {
             flags: Format::Default,
             n_spaces: 1,
             precision: 2,
-            thousands_separator: ",",
+            thousands_separator: ',',
         }
     }
 
@@ -90,7 +95,7 @@ impl ReprConfig for Format {
 
 #[derive(Eq, Copy, Clone, Debug, PartialEq)]
 pub enum ReprConfigVariant {
-    ThousandsSeparator(&'static str),
+    ThousandsSeparator(char),
     Precision(usize),
     Spaces(usize),
 }
@@ -124,203 +129,795 @@ macro_rules! ok_or {
     };
 }
 
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An unsigned integer a [`ByteSize`] can be stored in
+///
+/// Implemented for `u32`, `u64` and `u128`. Every computation runs on [`Int`],
+/// results are only narrowed back into the backing integer at the end.
+pub trait Backing:
+    sealed::Sealed
+    + Copy
+    + Ord
+    + fmt::Debug
+    + Into<Int>
+    + TryFrom<Int>
+    + core::ops::Add<Output = Self>
+    + core::ops::Sub<Output = Self>
+    + core::ops::AddAssign
+    + core::ops::SubAssign
+    + 'static
+{
+    const MAX: Self;
+
+    /// Narrows a value, saturating at [`Backing::MAX`]
+    #[inline]
+    fn saturating_from(value: Int) -> Self {
+        Self::try_from(value).unwrap_or(Self::MAX)
+    }
+}
+
+macro_rules! impl_backing {
+    ($($int:ty)+) => {
+        $(
+            impl sealed::Sealed for $int {}
+
+            impl Backing for $int {
+                const MAX: Self = <$int>::MAX;
+            }
+        )+
+    };
+}
+
+impl_backing!(u32 u64 u128);
+
+// how many of `variant` make up one `unit`, e.g. 1024 bytes or 8192 bits to a KiB
+fn per_unit(unit: Unit, variant: SizeVariant) -> Float {
+    let bits = f!(unit.effective_value());
+    match variant {
+        SizeVariant::Bit => bits,
+        SizeVariant::Byte => exec! {
+            unsafe { bits / f!(8) },
+            safely { saturate!(bits.checked_div(&{ f!(8) })) }
+        },
+    }
+}
+
+// a count of `variant`, converted to the variant `mode` calls for
+#[rustfmt::skip]
+fn prep_value(value: Int, variant: SizeVariant, mode: Mode) -> Float {
+    let value = f!(value);
+    match (variant, mode.contains(Mode::Bits)) {
+        (SizeVariant::Bit, false) => value / f!(8),
+        (SizeVariant::Byte, true) => exec! {
+            unsafe { value * f!(8) },
+            safely { saturate!(value.checked_mul(&{ f!(8) })) }
+        },
+        _ => value,
+    }
+}
+
+#[rustfmt::skip]
+fn repr(value: Int, variant: SizeVariant, mode: Mode) -> ByteSizeRepr {
+    let as_bits = mode.contains(Mode::Bits);
+    let no_prefix = mode.contains(Mode::NoPrefix);
+    let as_decimal = mode.contains(Mode::Decimal);
+    let mut value = prep_value(value, variant, mode);
+    let divisor = if as_decimal { f!(1000) } else { f!(1024) };
+    let unit_stack = if as_bits { sizes::BITS } else { sizes::BYTES };
+    let max_index = if no_prefix { 0 } else { unit_stack.len() - 1 };
+    let mut prefix_index = 0;
+    while prefix_index < max_index && value >= divisor {
+        value /= divisor;
+        prefix_index += 2;
+    }
+    if prefix_index > 0 && as_decimal { prefix_index -= 1 }
+    ByteSizeRepr::of(value, unit_stack[prefix_index])
+}
+
+fn repr_as(value: Int, variant: SizeVariant, unit: Unit) -> ByteSizeRepr {
+    let value = prep_value(value, variant, Mode::Bits) / f!(unit.effective_value());
+    ByteSizeRepr::of(value, unit)
+}
+
+// the largest unit of `variant` that fits a count of it a whole number of times
+fn repr_exact(value: Int, variant: SizeVariant) -> ExactRepr {
+    let units = if variant.is_bit() {
+        sizes::BITS
+    } else {
+        sizes::BYTES
+    };
+    let per_unit = |unit: &Unit| unit.effective_value() / variant.effective_value() as Int;
+    let unit = units
+        .iter()
+        .rev()
+        .find(|unit| value != 0 && value % per_unit(unit) == 0)
+        .unwrap_or(&units[0]);
+    ExactRepr(value / per_unit(unit), *unit, Format::Default)
+}
+
+// a count of `unit`, as a count of `variant` rounded down, or `None` past `Int::MAX`
+fn count(value: Value, unit: Unit, variant: SizeVariant) -> Option<Int> {
+    match value {
+        Value::Whole(value) => {
+            let (bits, per_variant) = (unit.effective_value(), variant.effective_value() as Int);
+            match bits % per_variant {
+                0 => value.checked_mul(bits / per_variant),
+                _ => value.checked_mul(bits).map(|bits| bits / per_variant),
+            }
+        }
+        #[cfg(feature = "lossless")]
+        Value::Fract(value) => value
+            .checked_mul(&per_unit(unit, variant))
+            .map(|value| i!(value)),
+        #[cfg(not(feature = "lossless"))]
+        Value::Fract(value) => match value * per_unit(unit, variant) {
+            value if value < Int::MAX as Float => Some(i!(value)),
+            _ => None,
+        },
+    }
+}
+
+/// A size, held as a count of bytes in a [`Backing`] integer
+///
+/// The width is `u64` unless stated otherwise. [`ByteSize::of`] and [`ByteSize::from_bytes`]
+/// build `u64` sizes, other widths have constructors of their own, e.g. `ByteSize::of_u128(1, YOBI_BYTE)`.
+/// Widening between widths is lossless through `From`, narrowing is checked through `TryFrom`.
+///
+/// Sizes that aren't a whole number of bytes are rounded down, see [`BitSize`] for those.
+///
+/// ```
+/// use xbytes::prelude::*;
+///
+/// let size = ByteSize::of(10, MEBI_BYTE);
+/// assert_eq!(size.to_string(), "10 MiB");
+///
+/// let a = ByteSize::of(1.2, GIGA_BYTE);
+/// let b = ByteSize::of(1.3, GIGA_BYTE);
+/// assert_eq!((a + b).repr(Mode::Decimal).to_string(), "2.50 GB");
+///
+/// let large = ByteSize::of_u128(1, YOBI_BYTE);
+/// assert_eq!(large.to_string(), "1 YiB");
+/// ```
 #[derive(Eq, Ord, Copy, Clone, Debug, PartialEq, PartialOrd)]
-pub struct ByteSize(Int);
+#[cfg_attr(
+    feature = "diesel",
+    derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow),
+    diesel(sql_type = diesel::sql_types::BigInt)
+)]
+pub struct ByteSize<T = u64>(pub(crate) T);
+
+/// A size, held as a count of bits in a [`Backing`] integer
+///
+/// For bit-granular sizes like network frames or bitmaps. Otherwise the same as [`ByteSize`],
+/// which it converts to and from: checked through `TryFrom`, rounding down to whole bytes through `From`.
+///
+/// ```
+/// use xbytes::prelude::*;
+///
+/// let frame = BitSize::of(1500, BYTE) + BitSize::from_bits(3);
+/// assert_eq!(frame.bits(), 12003);
+/// assert_eq!(frame.repr(Mode::Bits).to_string(), "11.72 Kib");
+///
+/// let bytes = ByteSize::from(frame);
+/// assert_eq!(bytes.bytes(), 1500);
+/// assert_eq!(BitSize::try_from(bytes), Ok(BitSize::from_bits(12000)));
+/// ```
+#[derive(Eq, Ord, Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct BitSize<T = u64>(pub(crate) T);
 
-impl ByteSize {
-    /// Create a ByteSize from a value and unit
+impl<T: Backing> ByteSize<T> {
+    // a size of `value` units, saturating at the upper bound of the backing integer
+    fn saturating_of(value: Float, unit: Unit) -> Self {
+        let value = exec! {
+            unsafe { value * per_unit(unit, SizeVariant::Byte) },
+            safely { saturate!(value.checked_mul(&per_unit(unit, SizeVariant::Byte))) }
+        };
+
+        ByteSize(T::saturating_from(i!(value)))
+    }
+
+    // byte count, saturating at the upper bound of the backing integer
+    #[inline]
+    #[cfg(feature = "std")]
+    pub(crate) fn saturating_from_bytes(value: Int) -> Self {
+        Self(T::saturating_from(value))
+    }
+
+    #[inline]
+    #[cfg(feature = "std")]
+    pub(crate) fn whole_bytes(&self) -> Int {
+        self.0.into()
+    }
+
+    #[inline]
+    pub const fn bytes(&self) -> T {
+        self.0
+    }
+
+    pub fn repr(&self, mode: Mode) -> ByteSizeRepr {
+        repr(self.0.into(), SizeVariant::Byte, mode)
+    }
+
+    pub fn repr_as(&self, unit: impl Into<Unit>) -> ByteSizeRepr {
+        repr_as(self.0.into(), SizeVariant::Byte, unit.into())
+    }
+
+    /// The size as a whole number of the largest byte unit that fits it exactly
+    ///
+    /// Always parses back to the same size, see [`ExactRepr`].
     ///
     /// ```
     /// use xbytes::prelude::*;
     ///
-    /// let size = ByteSize::of(10, MEBI_BYTE);
-    /// assert_eq!(size.to_string(), "10 MiB");
+    /// let size = ByteSize::of(1.5, MEBI_BYTE);
+    /// assert_eq!("1536 KiB", size.repr_exact().to_string());
+    /// assert_eq!("1.50 MiB", size.to_string());
+    /// assert_eq!(Ok(size), size.repr_exact().to_string().parse());
+    /// ```
+    pub fn repr_exact(&self) -> ExactRepr {
+        repr_exact(self.0.into(), SizeVariant::Byte)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn to_string_exact(&self) -> String {
+        self.repr_exact().to_string()
+    }
+
+    /// Parses any size the formatter writes out, given the mode it was formatted with
+    ///
+    /// Unlike [`FromStr`], takes every [`Format`], see [`Unit::parse_repr`] for how units are told apart.
     ///
-    /// let a = ByteSize::of(1.2, GIGA_BYTE);
-    /// let b = ByteSize::of(1.3, GIGA_BYTE);
-    /// assert_eq!((a + b).repr(Mode::Decimal).to_string(), "2.50 GB");
     /// ```
-    pub fn of(value: impl Into<Float>, unit: Unit) -> Self {
-        let u_value = exec! {
-            bits { f!(unit.effective_value()) },
-            nobits {
-                exec! {
-                    unsafe { f!(unit.effective_value()) / f!(8) },
-                    safely { saturate!(f!(unit.effective_value()).checked_div(&{ f!(8) })) }
-                }
-            }
-        };
+    /// use xbytes::prelude::*;
+    ///
+    /// let size = ByteSize::of(1.5, KIBI_BYTE);
+    /// let text = size.repr(Mode::Default).with(Format::LowerCaps).to_string();
+    /// assert_eq!("1.50 kib", text);
+    /// assert_eq!(Ok(size), ByteSize::parse_repr(&text, Mode::Default));
+    /// assert_eq!(Ok(BitSize::of(1.5, KIBI_BIT)), BitSize::parse_repr(&text, Mode::Bits));
+    /// ```
+    pub fn parse_repr(s: &str, mode: Mode) -> Result<Self, ParseError> {
+        parse(s, |unit| Unit::parse_repr(unit, mode)).and_then(Self::from_parsed)
+    }
 
-        let value = exec! {
-            unsafe { value.into() * f!(u_value) },
-            safely { saturate!(value.into().checked_mul(&{ f!(u_value) })) }
+    fn from_parsed((value, unit): (Value, Unit)) -> Result<Self, ParseError> {
+        let value = count(value, unit, SizeVariant::Byte).and_then(|value| T::try_from(value).ok());
+        ok_or!(value, ParseError::ValueOverflow).map(Self)
+    }
+}
+
+// an integer and a unit symbol, the shared grammar of the const parsers
+const fn parse_whole(s: &str) -> Result<(Int, Unit), ParseError> {
+    let s = s.as_bytes().trim_ascii();
+    if s.is_empty() {
+        return Err(ParseError::EmptyInput);
+    }
+    let (mut index, mut value): (_, Int) = (0, 0);
+    while index < s.len() && s[index].is_ascii_digit() {
+        let digit = (s[index] - b'0') as Int;
+        value = match value.checked_mul(10) {
+            Some(value) if value <= Int::MAX - digit => value + digit,
+            _ => return Err(ParseError::ValueOverflow),
         };
+        index += 1;
+    }
+    if index == 0 {
+        return Err(if s[0].is_ascii_alphabetic() {
+            ParseError::MissingValue
+        } else {
+            ParseError::InvalidValue
+        });
+    }
+    while index < s.len() && s[index].is_ascii_whitespace() {
+        index += 1;
+    }
+    if index == s.len() {
+        return Err(ParseError::MissingUnit);
+    }
+    if !s[index].is_ascii_alphabetic() {
+        return Err(ParseError::InvalidValue);
+    }
+    match Unit::from_symbol(s.split_at(index).1) {
+        Ok(unit) => Ok((value, unit)),
+        Err(err) => Err(err),
+    }
+}
 
-        ByteSize(i!(value))
+impl ByteSize {
+    /// Create a ByteSize from a value and unit
+    ///
+    /// Sizes past the range of a `u64` saturate at its upper bound,
+    /// see [`ByteSize::of_u128`] for wider sizes.
+    pub fn of(value: impl Into<Float>, unit: Unit) -> Self {
+        Self::saturating_of(value.into(), unit)
     }
 
     #[inline]
-    #[cfg(feature = "bits")]
-    pub const fn from_bits(value: Int) -> Self {
+    pub const fn from_bytes(value: u64) -> Self {
         Self(value)
     }
 
-    #[inline]
-    #[cfg(feature = "bits")]
-    pub const fn from_bytes(value: Int) -> Result<Self, ParseError> {
-        match ok_or!(value.checked_mul(8), ParseError::ValueOverflow) {
-            Ok(value) => Ok(Self(value)),
+    /// Parses an integer and a unit symbol, in const contexts
+    ///
+    /// Stricter than [`FromStr`]: no fractions, thousands separators or long unit names,
+    /// and sizes in bits have to make up whole bytes. See [`bytesize!`](crate::bytesize)
+    /// for literals checked at compile time.
+    ///
+    /// ```
+    /// use xbytes::{ByteSize, ParseError};
+    ///
+    /// const MAX_FRAME: ByteSize = match ByteSize::parse_const("16 MiB") {
+    ///     Ok(size) => size,
+    ///     Err(err) => panic!("{}", err.as_str()),
+    /// };
+    /// assert_eq!(Ok(MAX_FRAME), "16 MiB".parse());
+    /// assert_eq!(Err(ParseError::InvalidValue), ByteSize::parse_const("1.5 MiB"));
+    /// ```
+    pub const fn parse_const(s: &str) -> Result<Self, ParseError> {
+        match parse_whole(s) {
+            Ok((value, unit)) => Self::of_whole(value, unit),
             Err(err) => Err(err),
         }
     }
 
-    #[inline]
-    #[cfg(not(feature = "bits"))]
-    pub const fn from_bytes(value: Int) -> Self {
-        Self(value)
+    /// A size of `value` units, in const contexts
+    ///
+    /// Fails on sizes past the range of a `u64`, and on sizes in bits that don't make up whole bytes.
+    ///
+    /// ```
+    /// use xbytes::{sizes::*, ByteSize, ParseError};
+    ///
+    /// const BLOCK: ByteSize = ByteSize::kib(4);
+    /// static BUFFER: [u8; BLOCK.bytes() as usize] = [0; BLOCK.bytes() as usize];
+    ///
+    /// assert_eq!(Ok(BLOCK), ByteSize::from_unit(32, KIBI_BIT));
+    /// assert_eq!(Err(ParseError::ValueOverflow), ByteSize::from_unit(16, EXBI_BYTE));
+    /// assert_eq!(4096, BUFFER.len());
+    /// ```
+    pub const fn from_unit(value: u64, unit: Unit) -> Result<Self, ParseError> {
+        Self::of_whole(value as Int, unit)
     }
 
-    #[inline]
-    #[cfg(not(feature = "bits"))]
-    pub const fn from_bits(value: Int) -> Result<Self, ParseError> {
-        match ok_or!(value.checked_div(8), ParseError::ValueOverflow) {
-            Ok(value) => Ok(Self(value)),
-            Err(err) => Err(err),
+    const fn of_whole(value: Int, unit: Unit) -> Result<Self, ParseError> {
+        let bits = match value.checked_mul(unit.effective_value()) {
+            Some(bits) => bits,
+            None => return Err(ParseError::ValueOverflow),
+        };
+        if bits % 8 != 0 {
+            return Err(ParseError::InvalidValue);
+        }
+        if bits / 8 > u64::MAX as Int {
+            return Err(ParseError::ValueOverflow);
+        }
+        Ok(ByteSize((bits / 8) as u64))
+    }
+}
+
+// `ByteSize::kib(4)`, panicking (or failing to compile, in const contexts) on overflow,
+// next to `ByteSize::checked_kib(4)` returning `None` instead
+macro_rules! unit_constructors {
+    ($size:ident: $($name:ident, $checked:ident => $unit:ident),+) => {
+        impl $size {
+            $(
+                #[doc = concat!(
+                    "A size of `value` in [`", stringify!($unit), "`](sizes::", stringify!($unit), ")"
+                )]
+                #[doc = ""]
+                #[doc = "# Panics"]
+                #[doc = ""]
+                #[doc = concat!(
+                    "On sizes past the range of a `u64`, see [`", stringify!($size), "::",
+                    stringify!($checked), "`] for a fallible variant."
+                )]
+                #[inline]
+                pub const fn $name(value: u64) -> Self {
+                    match Self::from_unit(value, sizes::$unit) {
+                        Ok(size) => size,
+                        Err(err) => panic!("{}", err.as_str()),
+                    }
+                }
+
+                #[doc = concat!(
+                    "A size of `value` in [`", stringify!($unit), "`](sizes::", stringify!($unit),
+                    "), or `None` past the range of a `u64`"
+                )]
+                #[inline]
+                pub const fn $checked(value: u64) -> Option<Self> {
+                    match Self::from_unit(value, sizes::$unit) {
+                        Ok(size) => Some(size),
+                        Err(_) => None,
+                    }
+                }
+            )+
         }
+    };
+    ($($size:ident)+) => {
+        $(
+            #[rustfmt::skip]
+            unit_constructors!($size:
+                kb, checked_kb => KILO_BYTE, kib, checked_kib => KIBI_BYTE,
+                mb, checked_mb => MEGA_BYTE, mib, checked_mib => MEBI_BYTE,
+                gb, checked_gb => GIGA_BYTE, gib, checked_gib => GIBI_BYTE,
+                tb, checked_tb => TERA_BYTE, tib, checked_tib => TEBI_BYTE,
+                pb, checked_pb => PETA_BYTE, pib, checked_pib => PEBI_BYTE,
+                eb, checked_eb => EXA_BYTE, eib, checked_eib => EXBI_BYTE,
+                kbit, checked_kbit => KILO_BIT, kibit, checked_kibit => KIBI_BIT,
+                mbit, checked_mbit => MEGA_BIT, mibit, checked_mibit => MEBI_BIT,
+                gbit, checked_gbit => GIGA_BIT, gibit, checked_gibit => GIBI_BIT,
+                tbit, checked_tbit => TERA_BIT, tibit, checked_tibit => TEBI_BIT,
+                pbit, checked_pbit => PETA_BIT, pibit, checked_pibit => PEBI_BIT,
+                ebit, checked_ebit => EXA_BIT, eibit, checked_eibit => EXBI_BIT
+            );
+        )+
+    };
+}
+
+unit_constructors!(ByteSize BitSize);
+
+/// A [`ByteSize`] constant, parsed at compile time
+///
+/// Takes an integer and a unit symbol, anything else fails to compile.
+/// See [`ByteSize::parse_const`] for what's accepted.
+///
+/// ```
+/// use xbytes::{bytesize, ByteSize};
+///
+/// const MAX_FRAME: ByteSize = bytesize!("16 MiB");
+/// assert_eq!(Ok(MAX_FRAME), "16 MiB".parse());
+/// assert_eq!(bytesize!("2 kB"), bytesize!("16000 b"));
+/// ```
+///
+/// ```compile_fail
+/// const MAX_FRAME: xbytes::ByteSize = xbytes::bytesize!("16 MiBs");
+/// ```
+#[macro_export]
+macro_rules! bytesize {
+    ($literal:expr) => {{
+        const SIZE: $crate::ByteSize = match $crate::ByteSize::parse_const($literal) {
+            ::core::result::Result::Ok(size) => size,
+            ::core::result::Result::Err(err) => ::core::panic!("{}", err.as_str()),
+        };
+        SIZE
+    }};
+}
+
+impl<T: Backing> BitSize<T> {
+    // a size of `value` units, saturating at the upper bound of the backing integer
+    fn saturating_of(value: Float, unit: Unit) -> Self {
+        let value = exec! {
+            unsafe { value * per_unit(unit, SizeVariant::Bit) },
+            safely { saturate!(value.checked_mul(&per_unit(unit, SizeVariant::Bit))) }
+        };
+
+        BitSize(T::saturating_from(i!(value)))
     }
 
     #[inline]
-    #[cfg(feature = "bits")]
-    pub const fn bits(&self) -> Int {
+    pub const fn bits(&self) -> T {
         self.0
     }
 
-    #[inline]
-    #[cfg(feature = "bits")]
-    pub const fn bytes(&self) -> Result<Int, ParseError> {
-        ok_or!(self.0.checked_div(8), ParseError::ValueOverflow)
+    pub fn repr(&self, mode: Mode) -> ByteSizeRepr {
+        repr(self.0.into(), SizeVariant::Bit, mode)
     }
 
-    #[inline]
-    #[cfg(not(feature = "bits"))]
-    pub const fn bytes(&self) -> Int {
-        self.0
+    pub fn repr_as(&self, unit: impl Into<Unit>) -> ByteSizeRepr {
+        repr_as(self.0.into(), SizeVariant::Bit, unit.into())
+    }
+
+    /// The size as a whole number of the largest bit unit that fits it exactly
+    ///
+    /// Always parses back to the same size, see [`ExactRepr`].
+    pub fn repr_exact(&self) -> ExactRepr {
+        repr_exact(self.0.into(), SizeVariant::Bit)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn to_string_exact(&self) -> String {
+        self.repr_exact().to_string()
+    }
+
+    /// Parses any size the formatter writes out, given the mode it was formatted with
+    ///
+    /// See [`ByteSize::parse_repr`].
+    pub fn parse_repr(s: &str, mode: Mode) -> Result<Self, ParseError> {
+        parse(s, |unit| Unit::parse_repr(unit, mode)).and_then(Self::from_parsed)
+    }
+
+    fn from_parsed((value, unit): (Value, Unit)) -> Result<Self, ParseError> {
+        let value = count(value, unit, SizeVariant::Bit).and_then(|value| T::try_from(value).ok());
+        ok_or!(value, ParseError::ValueOverflow).map(Self)
+    }
+}
+
+impl BitSize {
+    /// Create a BitSize from a value and unit
+    ///
+    /// Sizes past the range of a `u64` saturate at its upper bound,
+    /// see [`BitSize::of_u128`] for wider sizes.
+    pub fn of(value: impl Into<Float>, unit: Unit) -> Self {
+        Self::saturating_of(value.into(), unit)
     }
 
     #[inline]
-    #[cfg(not(feature = "bits"))]
-    pub const fn bits(&self) -> Result<Int, ParseError> {
-        ok_or!(self.0.checked_mul(8), ParseError::ValueOverflow)
-    }
-
-    #[rustfmt::skip]
-    fn prep_value(&self, mode: Mode) -> Float {
-        let value = f!(self.0);
-        let wants_bits = mode.contains(Mode::Bits);
-        if exec! {
-            bits { !wants_bits },
-            nobits { wants_bits }
-        } {
-            exec! {
-                bits { value / f!(8) },
-                nobits { exec! {
-                    unsafe { value * f!(8) },
-                    safely { saturate!(value.checked_mul(&{ f!(8) })) }
-                } }
-            }
-        } else { value }
+    pub const fn from_bits(value: u64) -> Self {
+        Self(value)
     }
 
-    #[rustfmt::skip]
-    pub fn repr(&self, mode: Mode) -> ByteSizeRepr {
-        let as_bits = mode.contains(Mode::Bits);
-        let no_prefix = mode.contains(Mode::NoPrefix);
-        let as_decimal = mode.contains(Mode::Decimal);
-        let mut value = self.prep_value(mode);
-        let divisor = if as_decimal { f!(1000) } else { f!(1024) };
-        let unit_stack = if as_bits { sizes::BITS } else { sizes::BYTES };
-        let max_index = if no_prefix { 0 } else { unit_stack.len() - 1 };
-        let mut prefix_index = 0;
-        while prefix_index < max_index && value >= divisor {
-            value /= divisor;
-            prefix_index += 2;
+    /// Parses an integer and a unit symbol, in const contexts
+    ///
+    /// Accepts the same input as [`ByteSize::parse_const`], sizes in bits don't need
+    /// to make up whole bytes. See [`bitsize!`](crate::bitsize) for literals checked at
+    /// compile time.
+    ///
+    /// ```
+    /// use xbytes::{BitSize, ParseError};
+    ///
+    /// const LINK: BitSize = match BitSize::parse_const("100 Mb") {
+    ///     Ok(size) => size,
+    ///     Err(err) => panic!("{}", err.as_str()),
+    /// };
+    /// assert_eq!(Ok(LINK), "100 Mb".parse());
+    /// assert_eq!(Err(ParseError::ValueOverflow), BitSize::parse_const("2 EiB"));
+    /// ```
+    pub const fn parse_const(s: &str) -> Result<Self, ParseError> {
+        match parse_whole(s) {
+            Ok((value, unit)) => Self::of_whole(value, unit),
+            Err(err) => Err(err),
         }
-        if prefix_index > 0 && as_decimal { prefix_index -= 1 }
-        ByteSizeRepr::of(value, unit_stack[prefix_index])
     }
 
-    pub fn repr_as(&self, unit: impl Into<Unit>) -> ByteSizeRepr {
-        let unit = unit.into();
+    /// A size of `value` units, in const contexts
+    ///
+    /// Fails on sizes whose bit count is past the range of a `u64`.
+    ///
+    /// ```
+    /// use xbytes::{sizes::*, BitSize, ParseError};
+    ///
+    /// const LINK: BitSize = BitSize::mbit(100);
+    ///
+    /// assert_eq!(Ok(BitSize::from_bits(3)), BitSize::from_unit(3, BIT));
+    /// assert_eq!(Err(ParseError::ValueOverflow), BitSize::from_unit(2, EXBI_BYTE));
+    /// assert_eq!(100_000_000, LINK.bits());
+    /// ```
+    pub const fn from_unit(value: u64, unit: Unit) -> Result<Self, ParseError> {
+        Self::of_whole(value as Int, unit)
+    }
 
-        let value = self.prep_value(unit.mode()) / f!(unit.effective_value());
-        let value = exec! {
-            unsafe { value * f!(8) },
-            safely { saturate!(value.checked_mul(&{ f!(8) })) }
+    const fn of_whole(value: Int, unit: Unit) -> Result<Self, ParseError> {
+        match value.checked_mul(unit.effective_value()) {
+            Some(bits) if bits <= u64::MAX as Int => Ok(BitSize(bits as u64)),
+            _ => Err(ParseError::ValueOverflow),
+        }
+    }
+}
+
+/// A [`BitSize`] constant, parsed at compile time
+///
+/// The counterpart of [`bytesize!`](crate::bytesize), see [`BitSize::parse_const`]
+/// for what's accepted.
+///
+/// ```
+/// use xbytes::{bitsize, BitSize};
+///
+/// const LINK: BitSize = bitsize!("100 Mb");
+/// assert_eq!(Ok(LINK), "100 Mb".parse());
+/// assert_eq!(bitsize!("3 b").bits(), 3);
+/// ```
+///
+/// ```compile_fail
+/// const LINK: xbytes::BitSize = xbytes::bitsize!("2 EiB");
+/// ```
+#[macro_export]
+macro_rules! bitsize {
+    ($literal:expr) => {{
+        const SIZE: $crate::BitSize = match $crate::BitSize::parse_const($literal) {
+            ::core::result::Result::Ok(size) => size,
+            ::core::result::Result::Err(err) => ::core::panic!("{}", err.as_str()),
         };
+        SIZE
+    }};
+}
 
-        ByteSizeRepr::of(value, unit)
-    }
+// `of` and `from_bytes` only exist for `u64`, so that `ByteSize::of(..)` doesn't need the width
+// spelled out. Other widths name theirs, as in `ByteSize::of_u128(1, YOBI_BYTE)`
+macro_rules! impl_width_constructors {
+    ($($int:ty: $of:ident, $from:ident),+) => {
+        $(
+            impl ByteSize<$int> {
+                #[doc = concat!("Create a `ByteSize<", stringify!($int), ">` from a value and unit")]
+                ///
+                /// Sizes past the range of the backing integer saturate at its upper bound.
+                pub fn $of(value: impl Into<Float>, unit: Unit) -> Self {
+                    Self::saturating_of(value.into(), unit)
+                }
+
+                #[inline]
+                pub const fn $from(value: $int) -> Self {
+                    Self(value)
+                }
+            }
+
+            impl BitSize<$int> {
+                #[doc = concat!("Create a `BitSize<", stringify!($int), ">` from a value and unit")]
+                ///
+                /// Sizes past the range of the backing integer saturate at its upper bound.
+                pub fn $of(value: impl Into<Float>, unit: Unit) -> Self {
+                    Self::saturating_of(value.into(), unit)
+                }
+
+                #[inline]
+                pub const fn $from(value: $int) -> Self {
+                    Self(value)
+                }
+            }
+        )+
+    };
+}
+
+impl_width_constructors!(u32: of_u32, from_u32, u128: of_u128, from_u128);
+
+// `const` needs a concrete integer, so the bit/byte conversions are spelled out per width
+macro_rules! impl_const_conversions {
+    ($($int:ty)+) => {
+        $(
+            impl ByteSize<$int> {
+                #[inline]
+                pub const fn from_bits(value: $int) -> Result<Self, ParseError> {
+                    match ok_or!(value.checked_div(8), ParseError::ValueOverflow) {
+                        Ok(value) => Ok(Self(value)),
+                        Err(err) => Err(err),
+                    }
+                }
+
+                #[inline]
+                pub const fn bits(&self) -> Result<$int, ParseError> {
+                    ok_or!(self.0.checked_mul(8), ParseError::ValueOverflow)
+                }
+            }
+
+            impl BitSize<$int> {
+                #[inline]
+                pub const fn from_bytes(value: $int) -> Result<Self, ParseError> {
+                    match ok_or!(value.checked_mul(8), ParseError::ValueOverflow) {
+                        Ok(value) => Ok(Self(value)),
+                        Err(err) => Err(err),
+                    }
+                }
+
+                #[inline]
+                pub const fn bytes(&self) -> Result<$int, ParseError> {
+                    ok_or!(self.0.checked_div(8), ParseError::ValueOverflow)
+                }
+            }
+        )+
+    };
 }
 
-impl fmt::Display for ByteSize {
+impl_const_conversions!(u32 u64 u128);
+
+impl<T: Backing> fmt::Display for ByteSize<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Display::fmt(&self.repr(Mode::Default), f)
     }
 }
 
+impl<T: Backing> fmt::Display for BitSize<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.repr(Mode::Bits), f)
+    }
+}
+
+impl<T: Backing> TryFrom<ByteSize<T>> for BitSize<T> {
+    type Error = ParseError;
+
+    #[inline]
+    fn try_from(size: ByteSize<T>) -> Result<Self, Self::Error> {
+        let value: Int = size.0.into();
+        let value = value
+            .checked_mul(8)
+            .and_then(|value| T::try_from(value).ok());
+        ok_or!(value, ParseError::ValueOverflow).map(BitSize)
+    }
+}
+
+// any trailing bits that don't make up a whole byte are dropped
+impl<T: Backing> From<BitSize<T>> for ByteSize<T> {
+    #[inline]
+    fn from(size: BitSize<T>) -> Self {
+        let value: Int = size.0.into();
+        ByteSize(T::saturating_from(value / 8))
+    }
+}
+
+// widening is lossless, narrowing fails on sizes past the range of the narrower integer
+macro_rules! impl_widths {
+    ($size:ident: $($narrow:ty => $wide:ty),+) => {
+        $(
+            impl From<$size<$narrow>> for $size<$wide> {
+                #[inline]
+                fn from(size: $size<$narrow>) -> Self {
+                    $size(size.0.into())
+                }
+            }
+
+            impl TryFrom<$size<$wide>> for $size<$narrow> {
+                type Error = ParseError;
+
+                #[inline]
+                fn try_from(size: $size<$wide>) -> Result<Self, Self::Error> {
+                    size.0
+                        .try_into()
+                        .map($size)
+                        .map_err(|_| ParseError::ValueOverflow)
+                }
+            }
+        )+
+    };
+}
+
+impl_widths!(ByteSize: u32 => u64, u32 => u128, u64 => u128);
+impl_widths!(BitSize: u32 => u64, u32 => u128, u64 => u128);
+
 macro_rules! impl_ops {
-    ($($class:ident::$method:ident)+) => {
+    ($size:ident: $($class:ident::$method:ident)+) => {
         $(
-            impl std::ops::$class<Self> for ByteSize {
-                type Output = ByteSize;
+            impl<T: Backing> core::ops::$class<Self> for $size<T> {
+                type Output = Self;
                 fn $method(self, rhs: Self) -> Self::Output {
-                    ByteSize(std::ops::$class::$method(self.0, rhs.0))
+                    $size(core::ops::$class::$method(self.0, rhs.0))
                 }
             }
         )+
     };
-    (@ { $($class:ident::$method:ident)+ }) => {
+    (@ $size:ident: { $($class:ident::$method:ident)+ }) => {
         $(
-            impl<T: TryInto<Int>> std::ops::$class<T> for ByteSize {
-                type Output = ByteSize;
-                fn $method(self, rhs: T) -> Self::Output {
-                    let me = f!(self.0);
-                    ByteSize(
-                        i!(
-                            rhs.try_into()
-                                .map_or(me, |rhs| std::ops::$class::$method(me, f!(rhs)))
-                        ),
-                    )
+            impl<T: Backing, R: TryInto<Int>> core::ops::$class<R> for $size<T> {
+                type Output = Self;
+                #[allow(clippy::suspicious_arithmetic_impl)]
+                fn $method(self, rhs: R) -> Self::Output {
+                    let me: Int = self.0.into();
+                    let me = f!(me);
+                    $size(T::saturating_from(i!(
+                        rhs.try_into()
+                            .map_or(me, |rhs| core::ops::$class::$method(me, f!(rhs)))
+                    )))
                 }
             }
         )+
     };
-    (mut $($class:ident::$method:ident)+) => {
+    (mut $size:ident: $($class:ident::$method:ident)+) => {
         $(
-            impl std::ops::$class<Self> for ByteSize {
+            impl<T: Backing> core::ops::$class<Self> for $size<T> {
                 fn $method(&mut self, rhs: Self) {
-                    std::ops::$class::$method(&mut self.0, rhs.0)
+                    core::ops::$class::$method(&mut self.0, rhs.0)
                 }
             }
         )+
     };
-    (@ mut { $($class:ident::$method:ident)+ }) => {
+    (@ mut $size:ident: { $($class:ident::$method:ident => $op:ident::$op_method:ident)+ }) => {
         $(
-            impl<T: TryInto<Int>> std::ops::$class<T> for ByteSize {
-                fn $method(&mut self, rhs: T) {
-                    if let Ok(rhs) = rhs.try_into() {
-                        std::ops::$class::$method(&mut self.0, rhs)
-                    }
+            impl<T: Backing, R: TryInto<Int>> core::ops::$class<R> for $size<T> {
+                fn $method(&mut self, rhs: R) {
+                    *self = core::ops::$op::$op_method(*self, rhs)
                 }
             }
         )+
     };
 }
 
-impl_ops!(Add::add Sub::sub);
-impl_ops!(@ { Mul::mul Div::div });
-impl_ops!(mut AddAssign::add_assign SubAssign::sub_assign);
-impl_ops!(@ mut { MulAssign::mul_assign DivAssign::div_assign });
+impl_ops!(ByteSize: Add::add Sub::sub);
+impl_ops!(@ ByteSize: { Mul::mul Div::div });
+impl_ops!(mut ByteSize: AddAssign::add_assign SubAssign::sub_assign);
+impl_ops!(@ mut ByteSize: { MulAssign::mul_assign => Mul::mul DivAssign::div_assign => Div::div });
+
+impl_ops!(BitSize: Add::add Sub::sub);
+impl_ops!(@ BitSize: { Mul::mul Div::div });
+impl_ops!(mut BitSize: AddAssign::add_assign SubAssign::sub_assign);
+impl_ops!(@ mut BitSize: { MulAssign::mul_assign => Mul::mul DivAssign::div_assign => Div::div });
 
 #[cfg_attr(feature = "lossless", derive(Eq))]
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -329,14 +926,15 @@ pub struct ByteSizeRepr(Float, Unit, ReprFormat);
 #[cfg(feature = "lossless")]
 impl Ord for ByteSizeRepr {
     #[inline]
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         self.partial_cmp(other).unwrap()
     }
 }
 
+#[allow(clippy::non_canonical_partial_ord_impl)]
 impl PartialOrd for ByteSizeRepr {
     #[inline]
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         (self.1, self.0).partial_cmp(&(other.1, other.0))
     }
 }
@@ -352,32 +950,106 @@ impl ByteSizeRepr {
             ..*self
         }
     }
+
+    #[inline]
+    pub const fn unit(&self) -> Unit {
+        self.1
+    }
+
+    /// Writes the representation out, without allocating
+    ///
+    /// ```
+    /// use xbytes::{prelude::*, ArrayString};
+    /// use std::fmt::Write;
+    ///
+    /// let mut line = ArrayString::<32>::new();
+    /// line.write_str("read ").unwrap();
+    /// ByteSize::of(1.5, MEBI_BYTE).repr(Mode::Default).write_to(&mut line).unwrap();
+    /// assert_eq!("read 1.50 MiB", line);
+    /// ```
+    pub fn write_to(&self, w: &mut impl fmt::Write) -> fmt::Result {
+        write!(w, "{}", self)
+    }
+
+    /// Formats the representation into a fixed-capacity string
+    ///
+    /// Fails if it takes more than `N` bytes.
+    ///
+    /// ```
+    /// use xbytes::{prelude::*, ArrayString};
+    ///
+    /// let repr = ByteSize::of(2, GIBI_BYTE).repr(Mode::Default).with(Format::Long);
+    /// let text: ArrayString = repr.to_array_string().unwrap();
+    /// assert_eq!("2 GibiBytes", text);
+    /// assert!(repr.to_array_string::<8>().is_err());
+    /// ```
+    pub fn to_array_string<const N: usize>(&self) -> Result<ArrayString<N>, fmt::Error> {
+        let mut s = ArrayString::new();
+        self.write_to(&mut s)?;
+        Ok(s)
+    }
 }
 
-impl From<ByteSizeRepr> for ByteSize {
+impl<T: Backing> From<ByteSizeRepr> for ByteSize<T> {
     fn from(repr: ByteSizeRepr) -> Self {
-        ByteSize::of(repr.0, repr.1)
+        Self::saturating_of(repr.0, repr.1)
+    }
+}
+
+impl<T: Backing> From<ByteSizeRepr> for BitSize<T> {
+    fn from(repr: ByteSizeRepr) -> Self {
+        Self::saturating_of(repr.0, repr.1)
+    }
+}
+
+// counts the whole digits of a value, so separators can be placed ahead of time
+#[derive(Default)]
+struct WholeDigits(usize, bool);
+
+impl fmt::Write for WholeDigits {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            self.1 |= !c.is_ascii_digit();
+            if !self.1 {
+                self.0 += 1;
+            }
+        }
+        Ok(())
     }
 }
 
-// thousands separator
-// thsep("503") -> ['503']
-// thsep("405503") -> ['405', '503']
-// thsep("1234567") -> ['1', '234', '567']
-fn thsep(digits: &str) -> impl Iterator<Item = &str> {
-    let (chars, tip) = (digits.as_bytes(), digits.len() % 3);
-    if tip != 0 { Some(&chars[..tip]) } else { None }
-        .into_iter()
-        .chain(chars[tip..].chunks(3))
-        .map(|digits| std::str::from_utf8(digits).expect("unexpected non-utf8 char encountered"))
+// thousands separator, given the number of whole digits to come
+// "1234567.89" -> "1,234,567.89"
+struct ThousandsSeparated<W> {
+    inner: W,
+    separator: char,
+    whole_digits: usize,
+}
+
+impl<W: fmt::Write> fmt::Write for ThousandsSeparated<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if self.whole_digits == 0 || !c.is_ascii_digit() {
+                self.whole_digits = 0;
+                self.inner.write_char(c)?;
+                continue;
+            }
+            self.inner.write_char(c)?;
+            self.whole_digits -= 1;
+            if self.whole_digits != 0 && self.whole_digits % 3 == 0 {
+                self.inner.write_char(self.separator)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl fmt::Display for ByteSizeRepr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let (is_plural, has_fract);
+        let (is_plural, has_fract, hex);
         let flags = self.2.flags;
 
-        let value_part = {
+        {
             let (mut force_fraction, no_fraction) = (
                 flags.contains(Format::ForceFraction),
                 flags.contains(Format::NoFraction),
@@ -390,38 +1062,41 @@ impl fmt::Display for ByteSizeRepr {
                 }),
             );
             if !force_fraction && no_fraction {
-                value = value.trunc();
+                value = f_trunc!(value);
             }
             is_plural = !f_is_one!(value);
-            has_fract = force_fraction || !(no_fraction || f_is_zero!(value.fract()));
-            let mut value_part = if has_fract {
-                format!("{:#.1$}", value, precision)
-            } else {
-                format!("{}", value)
+            has_fract = force_fraction || !(no_fraction || f_is_zero!(f_fract!(value)));
+            hex = !has_fract && flags.contains(Format::Hex);
+            let write_value = |w: &mut dyn fmt::Write| {
+                if has_fract {
+                    write!(w, "{:#.1$}", value, precision)
+                } else if hex {
+                    write!(w, "{:#x}", i!(value))
+                } else {
+                    write!(w, "{}", value)
+                }
             };
-            if flags.contains(Format::ShowThousandsSeparator) {
-                let (whole, fract) = value_part
-                    .find('.')
-                    .map_or((&value_part[..], ""), |index| value_part.split_at(index));
-                let mut parts = thsep(whole);
-                let mut whole = String::with_capacity(whole.len() + ((whole.len() - 1) / 3));
-                whole.extend(parts.next().into_iter().chain(parts.flat_map(|s| {
-                    std::iter::once(self.2.thousands_separator).chain(std::iter::once(s))
-                })));
-                value_part = format!("{}{}", whole, fract);
+            if !hex && flags.contains(Format::ShowThousandsSeparator) {
+                let mut digits = WholeDigits::default();
+                write_value(&mut digits)?;
+                write_value(&mut ThousandsSeparated {
+                    inner: &mut *f,
+                    separator: self.2.thousands_separator,
+                    whole_digits: digits.0,
+                })?;
+            } else {
+                write_value(f)?;
             }
-            value_part
-        };
+        }
 
-        let spaces = {
-            if !flags.contains(Format::NoSpace) {
-                " ".repeat(self.2.n_spaces)
-            } else {
-                "".to_string()
+        // hex digits would run into the unit, "0x0B"
+        if hex || !flags.contains(Format::NoSpace) {
+            for _ in 0..self.2.n_spaces.max(hex as usize) {
+                f.write_char(' ')?;
             }
-        };
+        }
 
-        let unit_part = {
+        {
             let (sign_minus, alternate, sign_plus) = (f.sign_minus(), f.alternate(), f.sign_plus());
 
             let (initials, condensed, long) = if sign_minus || alternate || sign_plus {
@@ -438,28 +1113,106 @@ impl fmt::Display for ByteSizeRepr {
                 )
             };
 
-            let mut unit = if long {
-                self.1.symbol_long(
+            let (prefix, size_variant) = if long {
+                self.1.symbols_long(
                     (flags.contains(Format::ForcePlural) || (sign_plus && alternate))
                         || (!flags.contains(Format::NoPlural) && (is_plural || has_fract)),
                     !flags.contains(Format::NoMultiCaps),
                 )
             } else if condensed {
-                self.1.symbol_condensed().to_string()
+                (self.1.symbol_condensed(), "")
             } else if initials {
-                self.1.symbol_initials()
+                self.1.symbols_initials()
             } else {
-                self.1.symbol()
+                self.1.symbols()
             };
-            if flags.contains(Format::UpperCaps) {
-                unit = unit.to_uppercase()
-            } else if flags.contains(Format::LowerCaps) {
-                unit = unit.to_lowercase()
+            for c in prefix.chars().chain(size_variant.chars()) {
+                f.write_char(if flags.contains(Format::UpperCaps) {
+                    c.to_ascii_uppercase()
+                } else if flags.contains(Format::LowerCaps) {
+                    c.to_ascii_lowercase()
+                } else {
+                    c
+                })?;
             }
-            unit
-        };
+        }
+
+        Ok(())
+    }
+}
+
+/// A size, as a whole number of some unit
+///
+/// Unlike [`ByteSizeRepr`], nothing is rounded off: it always parses back to the size it came from.
+/// Only the [`Format`] flags that keep it that way are taken, `Long`, `NoPlural`, `ForcePlural`,
+/// `NoMultiCaps`, `ShowThousandsSeparator`, `NoSpace` and `Hex`. The rest either can't tell some
+/// units apart (`Initials`, `Condensed`, `LowerCaps`, `UpperCaps`) or have no fraction to act on.
+///
+/// ```
+/// use xbytes::prelude::*;
+///
+/// let size = ByteSize::of(1234, KIBI_BYTE);
+/// let repr = size.repr_exact().with(Format::Long | Format::ShowThousandsSeparator | Format::UpperCaps);
+/// assert_eq!("1,234 KibiBytes", repr.to_string());
+/// assert_eq!(Ok(size), repr.to_string().parse());
+/// ```
+#[derive(Eq, Copy, Clone, Debug, PartialEq)]
+pub struct ExactRepr(Int, Unit, Format);
+
+impl ExactRepr {
+    const LOSSLESS: Format = bitflags_const_or!(Format::{
+        Long | NoPlural | ForcePlural | NoMultiCaps | ShowThousandsSeparator | NoSpace | Hex
+    });
+
+    pub fn with(&self, format: Format) -> Self {
+        Self(self.0, self.1, self.2 | (format & Self::LOSSLESS))
+    }
+
+    #[inline]
+    pub const fn value(&self) -> Int {
+        self.0
+    }
 
-        write!(f, "{}{}{}", value_part, spaces, unit_part)
+    #[inline]
+    pub const fn unit(&self) -> Unit {
+        self.1
+    }
+}
+
+impl fmt::Display for ExactRepr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags = self.2;
+        if flags.contains(Format::Hex) {
+            write!(f, "{:#x}", self.0)?;
+        } else if flags.contains(Format::ShowThousandsSeparator) {
+            let mut digits = WholeDigits::default();
+            write!(digits, "{}", self.0)?;
+            write!(
+                ThousandsSeparated {
+                    inner: &mut *f,
+                    separator: ',',
+                    whole_digits: digits.0,
+                },
+                "{}",
+                self.0
+            )?;
+        } else {
+            write!(f, "{}", self.0)?;
+        }
+        if flags.contains(Format::Hex) || !flags.contains(Format::NoSpace) {
+            f.write_char(' ')?;
+        }
+        let (prefix, size_variant) = if flags.contains(Format::Long) {
+            self.1.symbols_long(
+                flags.contains(Format::ForcePlural)
+                    || (!flags.contains(Format::NoPlural) && self.0 != 1),
+                !flags.contains(Format::NoMultiCaps),
+            )
+        } else {
+            self.1.symbols()
+        };
+        f.write_str(prefix)?;
+        f.write_str(size_variant)
     }
 }
 
@@ -468,58 +1221,167 @@ macro_rules! parse_value {
         #[cfg(feature = "lossless")]
         let val = Float::from_str(&$value);
         #[cfg(not(feature = "lossless"))]
-        let val = <f64 as FromStr>::from_str($value);
+        let val = <f64 as FromStr>::from_str(&$value);
         val
     }};
 }
 
-impl FromStr for ByteSize {
-    type Err = ParseError;
+// an integer prefixed with its radix, like "0x1000", "0o777" or "0b1010", and what follows it
+// digits can be grouped with single underscores between them, "0x2000_0000"
+//
+// A prefix that isn't followed by a digit of its radix doesn't start a literal, so "0b" is
+// zero bits just like "0 b". `b`, `B` and `E` are hex digits, so a unit after a hex literal
+// needs a space: "0x10b" is 0x10b bytes, never 16 bits.
+pub(crate) fn parse_radix(s: &str) -> Result<Option<(Int, &str)>, ParseError> {
+    let (radix, digits) = match s.as_bytes() {
+        [b'0', b'x' | b'X', ..] => (16, &s[2..]),
+        [b'0', b'o' | b'O', ..] => (8, &s[2..]),
+        [b'0', b'b' | b'B', ..] => (2, &s[2..]),
+        _ => return Ok(None),
+    };
+    if !digits.starts_with(|c: char| c.is_digit(radix)) {
+        return Ok(None);
+    }
+    let mut end = 0;
+    let mut chars = digits.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c.is_digit(radix) {
+            end = i + 1;
+        } else if c != '_' || !chars.peek().is_some_and(|&(_, c)| c.is_digit(radix)) {
+            break;
+        }
+    }
+    let (digits, rest) = digits.split_at(end);
+    if rest.starts_with(|c: char| !(c.is_whitespace() || c.is_alphabetic()))
+        || (radix == 16 && rest.starts_with(char::is_alphabetic))
+    {
+        return Err(ParseError::InvalidValue);
+    }
+    let mut value: Int = 0;
+    for digit in digits.chars().filter_map(|c| c.to_digit(radix)) {
+        value = value
+            .checked_mul(radix as Int)
+            .and_then(|value| value.checked_add(digit as Int))
+            .ok_or(ParseError::ValueOverflow)?;
+    }
+    Ok(Some((value, rest)))
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.is_empty() {
-            Err(ParseError::EmptyInput)
-        } else {
-            let (mut commas, mut cursor, mut frac_pos) = (0, 0, None);
-            let index = s
-                .find(|c| {
-                    #[rustfmt::skip]
-                    if frac_pos.is_none() {
-                        if matches!(c, '.') { frac_pos = Some(cursor) };
-                        if matches!(c, ',') { commas += 1 };
-                    };
-                    cursor += 1;
-                    c.is_alphabetic() || c.is_whitespace()
-                })
-                .ok_or(ParseError::MissingUnit)?;
-            if matches!(index, 0) {
-                Err(ParseError::MissingValue)?
+// a list of sizes split on the commas `at` accepts, given the text before and after each,
+// so that the thousands separators in "1,024 KiB, 2,048 KiB" stay with their sizes
+pub(crate) fn split_list<'a>(
+    s: &'a str,
+    at: impl Fn(&str, &str) -> bool + 'a,
+) -> impl Iterator<Item = &'a str> {
+    let mut start = 0;
+    s.match_indices(',')
+        .map(|(index, _)| index)
+        .filter(move |&index| at(&s[..index], &s[index + 1..]))
+        .chain(core::iter::once(s.len()))
+        .map(move |end| {
+            let part = &s[start..end];
+            start = end + 1;
+            part
+        })
+}
+
+// whole values are kept as is, `Float` can't hold every integer exactly without `lossless`
+enum Value {
+    Whole(Int),
+    Fract(Float),
+}
+
+fn parse_number(value: &str) -> Result<Value, ParseError> {
+    match value.parse() {
+        Ok(value) => Ok(Value::Whole(value)),
+        Err(_) => parse_value!(value)
+            .map(Value::Fract)
+            .map_err(|_| ParseError::InvalidValue),
+    }
+}
+
+// the value and unit of a size, e.g. "1,024.5 KiB"
+fn parse(
+    s: &str,
+    parse_unit: impl FnOnce(&str) -> Result<Unit, ParseError>,
+) -> Result<(Value, Unit), ParseError> {
+    if s.is_empty() {
+        Err(ParseError::EmptyInput)
+    } else if let Some((value, unit)) = parse_radix(s)? {
+        // a bare number of bytes, as in "0x20000000"
+        let unit = match unit {
+            "" => sizes::BYTE,
+            unit => parse_unit(unit.trim_start_matches(|c: char| c.is_whitespace()))?,
+        };
+        Ok((Value::Whole(value), unit))
+    } else {
+        let (mut commas, mut cursor, mut frac_pos) = (0, 0, None);
+        let index = s
+            .find(|c| {
+                #[rustfmt::skip]
+                if frac_pos.is_none() {
+                    if matches!(c, '.') { frac_pos = Some(cursor) };
+                    if matches!(c, ',') { commas += 1 };
+                };
+                cursor += 1;
+                c.is_alphabetic() || c.is_whitespace()
+            })
+            .ok_or(ParseError::MissingUnit)?;
+        if matches!(index, 0) {
+            Err(ParseError::MissingValue)?
+        }
+        let (value, unit) = s.split_at(index);
+        let value = if !matches!(commas, 0) {
+            {
+                // ensure proper comma alignment
+                //  • valid   : '1,203.34' '10,293,344'
+                //  • invalid : '1,23,45' '1,2,3,4.342'
+                let value = &value[..frac_pos.unwrap_or(value.len())];
+                let mut parts = value.split(',');
+                #[rustfmt::skip]
+                if !({
+                    if !matches!((value.len() - commas) % 3, 0) { parts.next() } else { None }
+                        .map_or(true, |tip| tip.len() < 3)
+                } && parts.all(|part| part.len() == 3))
+                { Err(ParseError::InvalidThousandsFormat)? };
             }
-            let (value, unit) = s.split_at(index);
-            let value = if !matches!(commas, 0) {
-                {
-                    // ensure proper comma alignment
-                    //  • valid   : '1,203.34' '10,293,344'
-                    //  • invalid : '1,23,45' '1,2,3,4.342'
-                    let value = &value[..frac_pos.unwrap_or(value.len())];
-                    let mut parts = value.split(',');
-                    #[rustfmt::skip]
-                    if !({
-                        if !matches!((value.len() - commas) % 3, 0) { parts.next() } else { None }
-                            .map_or(true, |tip| tip.len() < 3)
-                    } && parts.all(|part| part.len() == 3))
-                    { Err(ParseError::InvalidThousandsFormat)? };
+            #[cfg(feature = "alloc")]
+            let value = &value.replacen(',', "", commas);
+            #[cfg(not(feature = "alloc"))]
+            let (mut buf, mut len) = ([0; 128], 0);
+            #[cfg(not(feature = "alloc"))]
+            let value = {
+                // without an allocator, strip the commas on the stack
+                for byte in value.bytes().filter(|b| *b != b',') {
+                    *buf.get_mut(len).ok_or(ParseError::ValueOverflow)? = byte;
+                    len += 1;
                 }
-                parse_value!(value.replacen(',', "", commas))
-            } else {
-                parse_value!(value)
-            }
-            .map_err(|_| ParseError::InvalidValue)?;
-            let unit = unit
-                .trim_start_matches(|c: char| c.is_whitespace())
-                .parse()?;
-            Ok(ByteSize::of(value, unit))
-        }
+                core::str::from_utf8(&buf[..len]).map_err(|_| ParseError::InvalidValue)?
+            };
+            parse_number(value)
+        } else {
+            parse_number(value)
+        }?;
+        Ok((
+            value,
+            parse_unit(unit.trim_start_matches(|c: char| c.is_whitespace()))?,
+        ))
+    }
+}
+
+impl<T: Backing> FromStr for ByteSize<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s, str::parse).and_then(Self::from_parsed)
+    }
+}
+
+impl<T: Backing> FromStr for BitSize<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s, str::parse).and_then(Self::from_parsed)
     }
 }
 
@@ -529,29 +1391,127 @@ mod tests {
 
     #[test]
     fn bytesize() {
-        let bytes = 1048576;
+        let bytes: u64 = 1048576;
 
-        let size = exec! {
-            bits { ByteSize::from_bits(bytes * 8) },
-            nobits { ByteSize::from_bytes(bytes) }
-        };
-
-        assert_eq!("1 MiB", format!("{}", size));
+        assert_eq!("1 MiB", format!("{}", ByteSize::from_bytes(bytes)));
+        assert_eq!("8 Mib", format!("{}", BitSize::from_bits(bytes * 8)));
     }
 
     #[test]
     fn bytesize_from_cmp() {
-        let left = exec! {
-            bits { ByteSize::from_bits(8388608) },
-            nobits { ByteSize::from_bytes(1048576) }
-        };
-
-        let right = exec! {
-            bits { ByteSize::from_bytes(1048576).unwrap() },
-            nobits { ByteSize::from_bits(8388608).unwrap() }
-        };
+        let left = ByteSize::from_bytes(1048576);
+        let right = ByteSize::<u64>::from_bits(8388608).unwrap();
+        assert_eq!(left, right);
 
+        let left = BitSize::from_bits(8388608);
+        let right = BitSize::<u64>::from_bytes(1048576).unwrap();
         assert_eq!(left, right);
+
+        const FRAME: Result<BitSize, ParseError> = BitSize::<u64>::from_bytes(1500);
+        assert_eq!(FRAME, Ok(BitSize::from_bits(12000)));
+        assert_eq!(
+            ByteSize::from_u128(u128::MAX).bits(),
+            Err(ParseError::ValueOverflow)
+        );
+    }
+
+    #[test]
+    fn parse_const() {
+        const MAX_FRAME: ByteSize = bytesize!("16 MiB");
+        assert_eq!(ByteSize::of(16, MEBI_BYTE), MAX_FRAME);
+
+        for (input, size) in [
+            ("1024B", ByteSize::of(1, KIBI_BYTE)),
+            (" 3  kB ", ByteSize::of(3, KILO_BYTE)),
+            ("3 KB", ByteSize::of(3, KILO_BYTE)),
+            ("8 Mb", ByteSize::of(1, MEGA_BYTE)),
+            ("2 EiB", ByteSize::of(2, EXBI_BYTE)),
+            ("0 YB", ByteSize::of(0, BYTE)),
+        ] {
+            assert_eq!(Ok(size), ByteSize::parse_const(input), "{:?}", input);
+        }
+
+        for (input, err) in [
+            ("", ParseError::EmptyInput),
+            ("MiB", ParseError::MissingValue),
+            ("16", ParseError::MissingUnit),
+            ("1.5 MiB", ParseError::InvalidValue),
+            ("1,024 KiB", ParseError::InvalidValue),
+            ("-1 KiB", ParseError::InvalidValue),
+            ("3 b", ParseError::InvalidValue),
+            ("16 MiBs", ParseError::InvalidSizeVariant),
+            ("16 XiB", ParseError::InvalidPrefix),
+            ("16 EiB", ParseError::ValueOverflow),
+            ("1 YB", ParseError::ValueOverflow),
+            (
+                "99999999999999999999999999999999999999999 B",
+                ParseError::ValueOverflow,
+            ),
+        ] {
+            assert_eq!(Err(err), ByteSize::parse_const(input), "{:?}", input);
+        }
+
+        const LINK: BitSize = bitsize!("100 Mb");
+        assert_eq!(BitSize::of(100, MEGA_BIT), LINK);
+
+        for (input, size) in [
+            ("3 b", Ok(BitSize::from_bits(3))),
+            ("2 KiB", Ok(BitSize::of(2, KIBI_BYTE))),
+            ("1 EiB", Ok(BitSize::of(1, EXBI_BYTE))),
+            ("2 EiB", Err(ParseError::ValueOverflow)),
+            ("1.5 Mb", Err(ParseError::InvalidValue)),
+            ("16 Mbs", Err(ParseError::InvalidSizeVariant)),
+        ] {
+            assert_eq!(size, BitSize::parse_const(input), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn const_constructors() {
+        const SIZES: [ByteSize; 4] = [
+            ByteSize::kib(4),
+            ByteSize::gb(2),
+            ByteSize::mbit(3),
+            ByteSize::eib(15),
+        ];
+        assert_eq!(
+            [
+                ByteSize::of(4, KIBI_BYTE),
+                ByteSize::of(2, GIGA_BYTE),
+                ByteSize::of(375, KILO_BYTE),
+                ByteSize::of(15, EXBI_BYTE),
+            ],
+            SIZES
+        );
+
+        assert_eq!(
+            Ok(ByteSize::of(1, MEBI_BYTE)),
+            ByteSize::from_unit(1, MEBI_BYTE)
+        );
+        assert_eq!(Ok(ByteSize::of(0, BYTE)), ByteSize::from_unit(0, YOBI_BYTE));
+        assert_eq!(Err(ParseError::InvalidValue), ByteSize::from_unit(7, BIT));
+        assert_eq!(
+            Err(ParseError::ValueOverflow),
+            ByteSize::from_unit(1, ZETTA_BYTE)
+        );
+        assert!(std::panic::catch_unwind(|| ByteSize::eib(16)).is_err());
+
+        const CHECKED: [Option<ByteSize>; 2] =
+            [ByteSize::checked_eib(15), ByteSize::checked_eib(16)];
+        assert_eq!([Some(ByteSize::of(15, EXBI_BYTE)), None], CHECKED);
+
+        const BITS: [BitSize; 3] = [BitSize::kib(4), BitSize::mbit(3), BitSize::eib(1)];
+        assert_eq!(
+            [
+                BitSize::of(4, KIBI_BYTE),
+                BitSize::of(3, MEGA_BIT),
+                BitSize::of(1, EXBI_BYTE),
+            ],
+            BITS
+        );
+        assert_eq!(Ok(BitSize::from_bits(7)), BitSize::from_unit(7, BIT));
+        assert_eq!(None, BitSize::checked_eib(2));
+        assert!(std::panic::catch_unwind(|| BitSize::eib(2)).is_err());
     }
 
     #[test]
@@ -571,7 +1531,7 @@ mod tests {
                 flags: Format::Default,
                 n_spaces: 1,
                 precision: 2,
-                thousands_separator: ","
+                thousands_separator: ','
             },
             ReprFormat::default()
         )
@@ -597,7 +1557,7 @@ mod tests {
         let format = ReprFormat::default()
             .with(Spaces(2))
             .with(Precision(2))
-            .with(ThousandsSeparator("_"))
+            .with(ThousandsSeparator('_'))
             .with(Format::Long)
             .with(Format::ShowThousandsSeparator);
 
@@ -630,13 +1590,13 @@ mod tests {
         );
         assert_eq!(
             "58375.28 EiB",
-            repr.with(ThousandsSeparator("_")).to_string()
+            repr.with(ThousandsSeparator('_')).to_string()
         );
         assert_eq!("58375.28EiB", repr.with(Format::NoSpace).to_string());
         assert_eq!(
             "58_375.28    EiB",
             repr.with(Spaces(4))
-                .with(ThousandsSeparator("_"))
+                .with(ThousandsSeparator('_'))
                 .with(Format::ShowThousandsSeparator)
                 .to_string()
         );
@@ -647,58 +1607,168 @@ mod tests {
                 .with(Format::ShowThousandsSeparator)
                 .to_string()
         );
+
+        let repr = ByteSizeRepr::of(f!(123456), KILO_BYTE).with(Format::ShowThousandsSeparator);
+        assert_eq!("123,456 KB", repr.to_string());
+        assert_eq!("123,456.000 KB", format!("{:.3}", repr));
+        assert_eq!(
+            "123 KB",
+            ByteSizeRepr::of(f!(123), KILO_BYTE)
+                .with(Format::ShowThousandsSeparator)
+                .to_string()
+        );
     }
 
     #[test]
-    fn bytesize_of() {
+    #[cfg(feature = "lossless")]
+    fn repr_stays_exact() {
+        let size = ByteSize::from_u128((1 << 80) + 1);
+        let repr = size.repr(Mode::Default);
         assert_eq!(
-            exec! {
-                bits { ByteSize(8) },
-                nobits { ByteSize(1) }
-            },
-            ByteSize::of(1, BYTE)
+            "1.00000000000000000000000082718061255302767487140869206996285356581211090087890625 YiB",
+            format!("{:.80}", repr)
         );
-
+        assert_eq!("1.00 YiB", repr.with(Format::ForceFraction).to_string());
         assert_eq!(
-            exec! {
-                bits { ByteSize(1) },
-                nobits { ByteSize(0) } // 0.125 (saturated)
-            },
-            ByteSize::of(1, BIT)
+            "1.000000000000000000000001 YB",
+            format!(
+                "{:.24}",
+                (ByteSize::of_u128(1, YOTTA_BYTE) + ByteSize::of_u128(1, BYTE)).repr(Mode::Decimal)
+            )
         );
+        assert_eq!(size, ByteSize::from(repr));
+        assert_eq!(size, ByteSize::of_u128(repr.0, YOBI_BYTE));
+    }
 
+    #[test]
+    fn repr_exact() {
+        let size = ByteSize::of(1.5, MEBI_BYTE);
         assert_eq!(
-            exec! {
-                bits { ByteSize(8388608) },
-                nobits { ByteSize(1048576) }
-            },
+            ExactRepr(1536, KIBI_BYTE, Format::Default),
+            size.repr_exact()
+        );
+        assert_eq!("0 B", ByteSize::<u64>(0).repr_exact().to_string());
+        assert_eq!("3 KB", ByteSize::<u64>(3000).repr_exact().to_string());
+        assert_eq!(
+            "2000 KiB",
+            ByteSize::<u64>(2048000).repr_exact().to_string()
+        );
+        assert_eq!("12003 b", BitSize::<u64>(12003).repr_exact().to_string());
+        assert_eq!("1 Kib", BitSize::<u64>(1024).repr_exact().to_string());
+        assert_eq!(
+            "18446744073709551615 B",
+            ByteSize::<u64>(u64::MAX).repr_exact().to_string()
+        );
+        assert_eq!(
+            "1 MebiByte",
             ByteSize::of(1, MEBI_BYTE)
+                .repr_exact()
+                .with(Format::Long | Format::LowerCaps | Format::Condensed)
+                .to_string()
         );
-
         assert_eq!(
-            exec! {
-                bits { ByteSize(1048576) },
-                nobits { ByteSize(131072) }
-            },
-            ByteSize::of(1, MEBI_BIT)
+            "1,536Kibibytes",
+            size.repr_exact()
+                .with(Format::Long | Format::NoMultiCaps | Format::ShowThousandsSeparator)
+                .with(Format::NoSpace)
+                .to_string()
         );
 
-        #[cfg(feature = "u128")]
+        let byte_sizes = [
+            0,
+            1,
+            999,
+            1536,
+            3000,
+            2048000,
+            (1 << 80) + 1,
+            u64::MAX as Int,
+            u128::MAX - 1,
+            u128::MAX,
+        ];
+        for bits in 0..=Format::all().bits() {
+            let format = Format::from_bits_truncate(bits);
+            for value in byte_sizes {
+                let size = ByteSize::<u128>(value);
+                let text = size.repr_exact().with(format).to_string();
+                assert_eq!(Ok(size), text.parse(), "{:?}", text);
+
+                let size = BitSize::<u128>(value);
+                let text = size.repr_exact().with(format).to_string();
+                assert_eq!(Ok(size), text.parse(), "{:?}", text);
+
+                if let Ok(value) = u64::try_from(value) {
+                    let size = ByteSize::<u64>(value);
+                    let text = size.repr_exact().with(format).to_string();
+                    assert_eq!(Ok(size), text.parse(), "{:?}", text);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parse_repr() {
+        let sizes = [0, 1, 1000, 1536, 1023 << 10, 3 << 30, u64::MAX];
+        for mode in 0..=Mode::all().bits() {
+            let mode = Mode::from_bits_truncate(mode);
+            for format in 0..=Format::all().bits() {
+                let format = Format::from_bits_truncate(format);
+                let digits = format & (Format::NoFraction | Format::ForceFraction | Format::Hex);
+                for value in sizes {
+                    let repr = ByteSize::<u64>(value).repr(mode);
+                    let text = repr.with(format).to_string();
+                    assert_eq!(
+                        repr.with(digits).to_string().parse::<ByteSize>(),
+                        ByteSize::parse_repr(&text, mode),
+                        "{:?} in {:?}",
+                        text,
+                        mode
+                    );
+
+                    let repr = BitSize::<u64>(value).repr(mode);
+                    let text = repr.with(format).to_string();
+                    assert_eq!(
+                        repr.with(digits).to_string().parse::<BitSize>(),
+                        BitSize::parse_repr(&text, mode),
+                        "{:?} in {:?}",
+                        text,
+                        mode
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bytesize_of() {
+        assert_eq!(ByteSize(1), ByteSize::of(1, BYTE));
+        assert_eq!(BitSize(8), BitSize::of(1, BYTE));
+
+        assert_eq!(ByteSize(0), ByteSize::of(1, BIT)); // 0.125 (saturated)
+        assert_eq!(BitSize(1), BitSize::of(1, BIT));
+
+        assert_eq!(ByteSize(1048576), ByteSize::of(1, MEBI_BYTE));
+        assert_eq!(BitSize(8388608), BitSize::of(1, MEBI_BYTE));
+
+        assert_eq!(ByteSize(131072), ByteSize::of(1, MEBI_BIT));
+        assert_eq!(BitSize(1048576), BitSize::of(1, MEBI_BIT));
+
         assert_eq!(
-            exec! {
-                bits { ByteSize(9671406556917033397649408) },
-                nobits { ByteSize(1208925819614629174706176) }
-            },
-            ByteSize::of(1, YOBI_BYTE)
+            ByteSize(1208925819614629174706176),
+            ByteSize::of_u128(1, YOBI_BYTE)
+        );
+        assert_eq!(
+            BitSize(9671406556917033397649408),
+            BitSize::of_u128(1, YOBI_BYTE)
         );
 
-        #[cfg(feature = "u128")]
         assert_eq!(
-            exec! {
-                bits { ByteSize(1208925819614629174706176) },
-                nobits { ByteSize(151115727451828646838272) }
-            },
-            ByteSize::of(1, YOBI_BIT)
+            ByteSize(151115727451828646838272),
+            ByteSize::of_u128(1, YOBI_BIT)
+        );
+        assert_eq!(
+            BitSize(1208925819614629174706176),
+            BitSize::of_u128(1, YOBI_BIT)
         );
     }
 
@@ -742,6 +1812,87 @@ mod tests {
         assert_eq!(ByteSize::of(1, TERA_BIT), size);
     }
 
+    #[test]
+    fn widths() {
+        // `of` and `from_bytes` don't need the width spelled out
+        let size = ByteSize::of(1, KIBI_BYTE);
+        assert_eq!(1024_u64, size.bytes());
+        assert_eq!(ByteSize::from_bytes(2047), size + ByteSize::of(1023, BYTE));
+        assert_eq!(BitSize::from_bits(8), BitSize::of(1, BYTE));
+        assert_eq!(1_u128 << 80, ByteSize::from_u128(1 << 80).bytes());
+        assert_eq!(BitSize::from_u32(8), BitSize::of_u32(1, BYTE));
+
+        let small = ByteSize::of_u32(3, MEBI_BYTE);
+        let size = ByteSize::<u64>::from(small);
+        assert_eq!(ByteSize::of(3, MEBI_BYTE), size);
+        assert_eq!(Ok(small), ByteSize::try_from(size));
+        assert_eq!(
+            size,
+            ByteSize::try_from(ByteSize::<u128>::from(size)).unwrap()
+        );
+
+        let large = ByteSize::of_u128(1, YOBI_BYTE);
+        assert_eq!(
+            Err(ParseError::ValueOverflow),
+            ByteSize::<u64>::try_from(large)
+        );
+        assert_eq!("1 YiB", large.to_string());
+
+        // parsing fails on sizes past the backing integer, rather than saturating
+        assert_eq!(
+            Ok(ByteSize(u64::MAX)),
+            "18446744073709551615 B".parse::<ByteSize>()
+        );
+        for input in ["18446744073709551616 B", "16 EiB", "16.5 EiB", "1Zb"] {
+            assert_eq!(
+                Err(ParseError::ValueOverflow),
+                input.parse::<ByteSize>(),
+                "{:?}",
+                input
+            );
+        }
+        assert_eq!(Ok(ByteSize(1 << 64)), "16 EiB".parse::<ByteSize<u128>>());
+        assert_eq!(
+            Err(ParseError::ValueOverflow),
+            "4 GiB".parse::<ByteSize<u32>>()
+        );
+        assert_eq!(Err(ParseError::ValueOverflow), "2 EiB".parse::<BitSize>());
+        assert_eq!(
+            Err(ParseError::ValueOverflow),
+            ByteSize::<u64>::parse_repr("16.00 EiB", Mode::Default)
+        );
+
+        // saturates at the upper bound of the backing integer
+        assert_eq!(ByteSize(u32::MAX), ByteSize::of_u32(4, GIBI_BYTE));
+        assert_eq!(ByteSize(u32::MAX), small * 2048);
+
+        let mut size = small;
+        size *= 2048;
+        assert_eq!(ByteSize(u32::MAX), size);
+    }
+
+    #[test]
+    fn bitsize() {
+        let frame = BitSize::of(1500, BYTE) + BitSize::from_bits(3);
+        assert_eq!("11.72 Kib", frame.to_string());
+        assert_eq!("12003 b", frame.repr_as(BIT).to_string());
+        assert_eq!("1500.375 B", format!("{:.3}", frame.repr_as(BYTE)));
+        assert_eq!(Ok(frame), "12,003 b".parse());
+        assert_eq!(Ok(1500), frame.bytes());
+
+        let bytes = ByteSize::from(frame);
+        assert_eq!(ByteSize::of(1500, BYTE), bytes);
+        assert_eq!(Ok(BitSize::of(1500, BYTE)), BitSize::try_from(bytes));
+        assert_eq!(
+            Err(ParseError::ValueOverflow),
+            BitSize::try_from(ByteSize(u32::MAX))
+        );
+
+        let size = ByteSize::of(1, KILO_BIT);
+        assert_eq!("1 Kb", size.repr_as(KILO_BIT).to_string());
+        assert_eq!("125 B", size.repr_as(BYTE).to_string());
+    }
+
     #[test]
     fn byte_size_mode() {
         let size = ByteSize::of(1.50, MEBI_BYTE);
@@ -853,6 +2004,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::inconsistent_digit_grouping)]
     fn parse_thousands_separator() {
         assert_eq!(
             Ok(ByteSize::of(1_024, MEBI_BYTE)),
@@ -879,4 +2031,95 @@ mod tests {
             "2,68,43,54,56 KiB".parse::<ByteSize>()
         );
     }
+
+    #[test]
+    fn parse_radix() {
+        let map = [
+            ("0x1000 B", Ok(ByteSize::of(4, KIBI_BYTE))),
+            ("0x20000000", Ok(ByteSize::of(512, MEBI_BYTE))),
+            ("0X2000_0000", Ok(ByteSize::of(512, MEBI_BYTE))),
+            ("0o777 KiB", Ok(ByteSize::of(511, KIBI_BYTE))),
+            ("0b1010 KiB", Ok(ByteSize::of(10, KIBI_BYTE))),
+            ("0o777KiB", Ok(ByteSize::of(511, KIBI_BYTE))),
+            ("0xab B", Ok(ByteSize::from_bytes(171))),
+            ("0xaf", Ok(ByteSize::from_bytes(175))),
+            ("0x1E", Ok(ByteSize::from_bytes(30))),
+            ("0x40 Kb", Ok(ByteSize::of(8, KILO_BYTE))),
+            ("0x10 KibiBytes", Ok(ByteSize::of(16, KIBI_BYTE))),
+            ("0B", Ok(ByteSize::from_bytes(0))),
+            ("0b", Ok(ByteSize::from_bytes(0))),
+            ("0 bits", Ok(ByteSize::from_bytes(0))),
+            ("0x10KiB", Err(ParseError::InvalidValue)),
+            ("0x10EiB", Err(ParseError::InvalidValue)),
+            ("0xaB", Ok(ByteSize::from_bytes(171))),
+            ("0x1b", Ok(ByteSize::from_bytes(27))),
+            ("0x1000b", Ok(ByteSize::from_bytes(65547))),
+            ("0x1_b", Ok(ByteSize::from_bytes(27))),
+            ("0x1_", Err(ParseError::InvalidValue)),
+            ("0x1__2", Err(ParseError::InvalidValue)),
+            ("0x_1", Err(ParseError::InvalidSizeVariant)),
+            ("0b1_ KiB", Err(ParseError::InvalidValue)),
+            ("0x20000000 ", Err(ParseError::EmptyInput)),
+            ("0x", Err(ParseError::InvalidSizeVariant)),
+            ("0x KiB", Err(ParseError::InvalidPrefix)),
+            ("0o8 KiB", Err(ParseError::InvalidPrefix)),
+            ("0b2 KiB", Err(ParseError::InvalidPrefix)),
+            ("0x1.8 KiB", Err(ParseError::InvalidValue)),
+            ("0x1,000 KiB", Err(ParseError::InvalidValue)),
+            ("0x10 XB", Err(ParseError::InvalidPrefix)),
+            (
+                "0x1_0000_0000_0000_0000_0000_0000_0000_0000 B",
+                Err(ParseError::ValueOverflow),
+            ),
+        ];
+
+        for (value, size) in map {
+            assert_eq!(size, value.parse::<ByteSize>(), "{:?}", value);
+        }
+        assert_eq!(
+            Ok(ByteSize::from_bytes(u64::MAX)),
+            "0xffff_ffff_ffff_ffff B".parse()
+        );
+        assert_eq!(
+            Err(ParseError::ValueOverflow),
+            "0x1_0000_0000_0000_0000 B".parse::<ByteSize>()
+        );
+        assert_eq!(Ok(BitSize::from_bits(0x1000)), "0x1000 b".parse());
+        assert_eq!(Ok(BitSize::from_bits(1)), "0b1b".parse());
+    }
+
+    #[test]
+    fn format_hex() {
+        let size = ByteSize::of(1000, MEBI_BYTE);
+        assert_eq!(
+            "0x3e8 MiB",
+            size.repr(Mode::Default).with(Format::Hex).to_string()
+        );
+        assert_eq!(
+            "0x3e8 MiB",
+            size.repr(Mode::Default)
+                .with(Format::Hex | Format::ShowThousandsSeparator | Format::NoSpace)
+                .to_string()
+        );
+        assert_eq!(
+            "0x3e8  MiB",
+            size.repr(Mode::Default)
+                .with(Format::Hex)
+                .with(Spaces(2))
+                .to_string()
+        );
+        let size = ByteSize::of(1.5, KIBI_BYTE);
+        assert_eq!(
+            "1.50 KiB",
+            size.repr(Mode::Default).with(Format::Hex).to_string()
+        );
+        assert_eq!(
+            "0x1 KiB",
+            size.repr(Mode::Default)
+                .with(Format::Hex | Format::NoFraction)
+                .to_string()
+        );
+        assert_eq!("0x600 B", size.repr_exact().with(Format::Hex).to_string());
+        assert_eq!(Ok(size), "0x600 B".parse());
+    }
 }
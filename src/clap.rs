@@ -0,0 +1,387 @@
+//! Value parsers for [`clap`] arguments
+//!
+//! [`ByteSize`], [`Unit`] and [`Mode`] implement [`ValueParserFactory`], so
+//! derived arguments of these types need no `value_parser` attribute at all.
+//!
+//! ```
+//! use clap::Parser;
+//! use xbytes::{clap::ByteSizeParser, prelude::*};
+//!
+//! #[derive(Parser)]
+//! struct Cli {
+//!     #[arg(long)]
+//!     limit: ByteSize,
+//!     #[arg(long, value_parser = ByteSizeParser::new().with_max(ByteSize::of(1, GIBI_BYTE)))]
+//!     chunk: Option<ByteSize>,
+//! }
+//!
+//! let cli = Cli::try_parse_from(["app", "--limit", "10 MiB", "--chunk", "64 KiB"]).unwrap();
+//! assert_eq!(cli.limit, ByteSize::of(10, MEBI_BYTE));
+//! assert!(Cli::try_parse_from(["app", "--limit", "2 GiB", "--chunk", "2 GiB"]).is_err());
+//! ```
+
+use super::{sizes, ByteSize, Mode, ParseError, Unit};
+use ::clap::{
+    builder::{PossibleValue, StyledStr, TypedValueParser, ValueParserFactory},
+    error::{ContextKind, ContextValue, ErrorKind},
+    Arg, Command, Error,
+};
+use std::ffi::OsStr;
+
+fn to_str<'a>(cmd: &Command, arg: Option<&Arg>, value: &'a OsStr) -> Result<&'a str, Error> {
+    ::clap::builder::StringValueParser::new()
+        .parse_ref(cmd, arg, value)
+        .map(|_| value.to_str().unwrap_or_default())
+}
+
+// a validation error carrying `message`, along with tips on how to fix the value
+//
+// clap only renders the context of errors raised by its own parsers, so the message is
+// written out in full, and the context is kept for anyone inspecting the error
+fn invalid(
+    cmd: &Command,
+    arg: Option<&Arg>,
+    value: &OsStr,
+    message: String,
+    tips: Vec<String>,
+) -> Error {
+    use std::fmt::Write;
+
+    let arg = arg.map_or_else(|| "...".to_string(), Arg::to_string);
+    let value = value.to_string_lossy().into_owned();
+    let mut rendered = format!("invalid value '{}' for '{}': {}\n", value, arg, message);
+    if !tips.is_empty() {
+        rendered.push('\n');
+    }
+    for tip in &tips {
+        let _ = writeln!(rendered, "  tip: {}", tip);
+    }
+    let mut err = Error::raw(ErrorKind::ValueValidation, rendered).with_cmd(cmd);
+    err.insert(ContextKind::InvalidArg, ContextValue::String(arg));
+    err.insert(ContextKind::InvalidValue, ContextValue::String(value));
+    err.insert(ContextKind::Custom, ContextValue::String(message));
+    if !tips.is_empty() {
+        err.insert(
+            ContextKind::Suggested,
+            ContextValue::StyledStrs(tips.into_iter().map(StyledStr::from).collect()),
+        );
+    }
+    err
+}
+
+// tips for a unit that failed to parse
+fn unit_tips(unit: &str) -> Vec<String> {
+    let unit = unit.trim();
+    let symbols = sizes::ALL.iter().map(Unit::symbol);
+    let similar = symbols
+        .clone()
+        .filter(|symbol| symbol.eq_ignore_ascii_case(unit))
+        .map(|symbol| format!("'{}'", symbol))
+        .collect::<Vec<_>>();
+    if similar.is_empty() {
+        vec![format!(
+            "valid units are {}",
+            symbols.collect::<Vec<_>>().join(", ")
+        )]
+    } else {
+        vec![format!("did you mean {}?", similar.join(" or "))]
+    }
+}
+
+fn size_tips(input: &str, err: ParseError) -> Vec<String> {
+    let unit = || input.trim_start_matches(|c: char| !(c.is_alphabetic() || c.is_whitespace()));
+    match err {
+        ParseError::EmptyInput => vec!["specify a size, like '10 MiB'".to_string()],
+        ParseError::MissingUnit => vec![format!(
+            "add a unit, like '{0} B' or '{0} MiB'",
+            input.trim()
+        )],
+        ParseError::MissingValue => {
+            vec![format!("start with a number, like '10 {}'", unit().trim())]
+        }
+        ParseError::InvalidThousandsFormat => {
+            vec!["group digits in threes, like '1,048,576 B'".to_string()]
+        }
+        ParseError::InvalidPrefix | ParseError::InvalidSizeVariant => unit_tips(unit()),
+        #[cfg(not(feature = "case-insensitive"))]
+        ParseError::InvalidUnitCaseFormat | ParseError::InvalidPrefixCaseFormat => {
+            unit_tips(unit())
+        }
+        _ => vec![],
+    }
+}
+
+/// Parses a [`ByteSize`], optionally within bounds
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ByteSizeParser {
+    min: Option<ByteSize>,
+    max: Option<ByteSize>,
+}
+
+impl ByteSizeParser {
+    pub const fn new() -> Self {
+        Self {
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Reject sizes smaller than `min`
+    pub const fn with_min(self, min: ByteSize) -> Self {
+        Self {
+            min: Some(min),
+            ..self
+        }
+    }
+
+    /// Reject sizes larger than `max`
+    pub const fn with_max(self, max: ByteSize) -> Self {
+        Self {
+            max: Some(max),
+            ..self
+        }
+    }
+}
+
+impl TypedValueParser for ByteSizeParser {
+    type Value = ByteSize;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, Error> {
+        let input = to_str(cmd, arg, value)?;
+        let size = input
+            .parse::<ByteSize>()
+            .map_err(|err| invalid(cmd, arg, value, err.to_string(), size_tips(input, err)))?;
+        if let Some(min) = self.min.filter(|min| size < *min) {
+            return Err(invalid(
+                cmd,
+                arg,
+                value,
+                format!("size must be at least {}", min),
+                vec![],
+            ));
+        }
+        if let Some(max) = self.max.filter(|max| size > *max) {
+            return Err(invalid(
+                cmd,
+                arg,
+                value,
+                format!("size must be at most {}", max),
+                vec![],
+            ));
+        }
+        Ok(size)
+    }
+}
+
+impl ValueParserFactory for ByteSize {
+    type Parser = ByteSizeParser;
+
+    fn value_parser() -> Self::Parser {
+        ByteSizeParser::new()
+    }
+}
+
+/// Parses a [`Unit`], listing every unit in the help text
+#[derive(Copy, Clone, Debug, Default)]
+pub struct UnitParser;
+
+impl TypedValueParser for UnitParser {
+    type Value = Unit;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, Error> {
+        let input = to_str(cmd, arg, value)?;
+        input
+            .parse::<Unit>()
+            .map_err(|err| invalid(cmd, arg, value, err.to_string(), unit_tips(input)))
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(
+            sizes::ALL
+                .iter()
+                .map(|unit| PossibleValue::new(unit.symbol())),
+        ))
+    }
+}
+
+impl ValueParserFactory for Unit {
+    type Parser = UnitParser;
+
+    fn value_parser() -> Self::Parser {
+        UnitParser
+    }
+}
+
+const MODES: [(&str, Mode); 4] = [
+    ("default", Mode::Default),
+    ("bits", Mode::Bits),
+    ("decimal", Mode::Decimal),
+    ("no-prefix", Mode::NoPrefix),
+];
+
+/// Parses a [`Mode`] from comma-separated flags, like `decimal,bits`
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ModeParser;
+
+impl TypedValueParser for ModeParser {
+    type Value = Mode;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, Error> {
+        let input = to_str(cmd, arg, value)?;
+        input.split(',').try_fold(Mode::Default, |mode, flag| {
+            match MODES.iter().find(|(name, _)| *name == flag.trim()) {
+                Some((_, flag)) => Ok(mode | *flag),
+                None => Err(invalid(
+                    cmd,
+                    arg,
+                    value,
+                    format!("unknown mode '{}'", flag.trim()),
+                    vec![format!(
+                        "combine any of {}, like 'decimal,bits'",
+                        MODES.map(|(name, _)| name).join(", ")
+                    )],
+                )),
+            }
+        })
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(
+            MODES.iter().map(|(name, _)| PossibleValue::new(*name)),
+        ))
+    }
+}
+
+impl ValueParserFactory for Mode {
+    type Parser = ModeParser;
+
+    fn value_parser() -> Self::Parser {
+        ModeParser
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::sizes::*, *};
+    use ::clap::value_parser;
+
+    fn parse<P>(parser: P, value: &str) -> Result<P::Value, Error>
+    where
+        P: TypedValueParser,
+        P::Value: Clone + Send + Sync,
+    {
+        Command::new("app")
+            .arg(Arg::new("size").long("size").value_parser(parser))
+            .try_get_matches_from(["app", "--size", value])
+            .map(|mut matches| matches.remove_one::<P::Value>("size").unwrap())
+    }
+
+    fn tips(err: &Error) -> Vec<String> {
+        match err.get(ContextKind::Suggested) {
+            Some(ContextValue::StyledStrs(tips)) => {
+                tips.iter().map(|tip| tip.to_string()).collect()
+            }
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn parse_bytesize() {
+        assert_eq!(
+            ByteSize::of(1.5, GIBI_BYTE),
+            parse(value_parser!(ByteSize), "1.5 GiB").unwrap()
+        );
+
+        let err = parse(ByteSizeParser::new(), "1024").unwrap_err();
+        assert_eq!(ErrorKind::ValueValidation, err.kind());
+        assert!(err.to_string().contains("missing unit"));
+        assert_eq!(vec!["add a unit, like '1024 B' or '1024 MiB'"], tips(&err));
+
+        let err = parse(ByteSizeParser::new(), "1,00 B").unwrap_err();
+        assert_eq!(
+            vec!["group digits in threes, like '1,048,576 B'"],
+            tips(&err)
+        );
+    }
+
+    #[test]
+    fn render_errors() {
+        let err = parse(ByteSizeParser::new(), "1024").unwrap_err();
+        assert_eq!(
+            Some(&ContextValue::String("missing unit".to_string())),
+            err.get(ContextKind::Custom)
+        );
+        assert_eq!(
+            "error: invalid value '1024' for '--size <size>': missing unit\n\
+             \n  tip: add a unit, like '1024 B' or '1024 MiB'\n",
+            err.to_string()
+        );
+
+        let err = parse(
+            ByteSizeParser::new().with_min(ByteSize::of(4, KIBI_BYTE)),
+            "1 KiB",
+        );
+        assert_eq!(
+            "error: invalid value '1 KiB' for '--size <size>': size must be at least 4 KiB\n",
+            err.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "case-insensitive"))]
+    fn suggest_units() {
+        let err = parse(ByteSizeParser::new(), "10 mib").unwrap_err();
+        assert_eq!(vec!["did you mean 'Mib' or 'MiB'?"], tips(&err));
+
+        let err = parse(UnitParser, "kib").unwrap_err();
+        assert_eq!(vec!["did you mean 'Kib' or 'KiB'?"], tips(&err));
+
+        let err = parse(ByteSizeParser::new(), "10 XB").unwrap_err();
+        assert!(tips(&err)[0].starts_with("valid units are b, B, Kb, Kib, KB, KiB"));
+    }
+
+    #[test]
+    fn bounds() {
+        let parser = ByteSizeParser::new()
+            .with_min(ByteSize::of(4, KIBI_BYTE))
+            .with_max(ByteSize::of(1, MEBI_BYTE));
+        assert_eq!(ByteSize::of(4, KIBI_BYTE), parse(parser, "4 KiB").unwrap());
+        assert_eq!(ByteSize::of(1, MEBI_BYTE), parse(parser, "1 MiB").unwrap());
+
+        let err = parse(parser, "1 KiB").unwrap_err();
+        assert!(err.to_string().contains("size must be at least 4 KiB"));
+        let err = parse(parser, "1.5 MiB").unwrap_err();
+        assert!(err.to_string().contains("size must be at most 1 MiB"));
+    }
+
+    #[test]
+    fn parse_unit_and_mode() {
+        assert_eq!(MEGA_BIT, parse(value_parser!(Unit), "Mb").unwrap());
+        assert_eq!(
+            Mode::Decimal | Mode::Bits,
+            parse(value_parser!(Mode), "decimal,bits").unwrap()
+        );
+        assert_eq!(Mode::Default, parse(ModeParser, "default").unwrap());
+
+        let err = parse(ModeParser, "decimal,hex").unwrap_err();
+        assert!(err.to_string().contains("unknown mode 'hex'"));
+
+        let units = UnitParser.possible_values().unwrap().count();
+        assert_eq!(sizes::ALL.len(), units);
+    }
+}
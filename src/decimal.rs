@@ -0,0 +1,550 @@
+use super::{gcd, Int, ParseError};
+use core::{
+    cmp::Ordering,
+    fmt::{self, Write},
+    ops,
+    str::FromStr,
+};
+
+const HALF: u32 = Int::BITS / 2;
+const LOW: Int = Int::MAX >> HALF;
+
+// full product of two integers, as (high, low) halves
+const fn widening_mul(a: Int, b: Int) -> (Int, Int) {
+    let (a_hi, a_lo, b_hi, b_lo) = (a >> HALF, a & LOW, b >> HALF, b & LOW);
+    let (lo, mid_1, mid_2, hi) = (a_lo * b_lo, a_hi * b_lo, a_lo * b_hi, a_hi * b_hi);
+    let (mid, carry) = mid_1.overflowing_add(mid_2);
+    let (lo, borrow) = lo.overflowing_add(mid << HALF);
+    let hi = hi + (mid >> HALF) + ((carry as Int) << HALF) + borrow as Int;
+    (hi, lo)
+}
+
+// (a * b) / c and (a * b) % c, or None if the quotient overflows
+const fn mul_div(a: Int, b: Int, c: Int) -> Option<(Int, Int)> {
+    if let Some(product) = a.checked_mul(b) {
+        return Some((product / c, product % c));
+    }
+    let (hi, lo) = widening_mul(a, b);
+    if hi >= c {
+        return None;
+    }
+    // long division, one bit at a time
+    let (mut rem, mut quot, mut bit) = (hi, 0, Int::BITS);
+    while bit > 0 {
+        bit -= 1;
+        let carry = rem >> (Int::BITS - 1);
+        rem = (rem << 1) | ((lo >> bit) & 1);
+        quot <<= 1;
+        if carry == 1 || rem >= c {
+            rem = rem.wrapping_sub(c);
+            quot |= 1;
+        }
+    }
+    Some((quot, rem))
+}
+
+/// An exact, non-negative number, backing [`Float`](super::Float) under the `lossless` feature
+///
+/// Sizes are whole numbers, and units are powers of 1000 or 1024, so a size
+/// in any unit is a whole part plus a remainder over some divisor. Both are
+/// kept as integers, so repeated division (as in [`ByteSize::repr`]) stays exact.
+///
+/// Precision is only ever given up once a divisor would overflow [`Int`], at
+/// which point the remainder is rounded down to the divisor it already has.
+///
+/// Like the integer types, `*` panics on overflow and `/` on division by zero,
+/// see [`Decimal::checked_mul`] and [`Decimal::checked_div`] for ways around that.
+///
+/// [`ByteSize::repr`]: super::ByteSize::repr
+#[derive(Eq, Copy, Hash, Clone, Debug, PartialEq)]
+pub struct Decimal {
+    whole: Int,
+    // always reduced, with `rem < div`, and `div == 1` when `rem == 0`
+    rem: Int,
+    div: Int,
+}
+
+impl Decimal {
+    pub const ZERO: Self = Self::from_int(0);
+    pub const ONE: Self = Self::from_int(1);
+    pub const MAX: Self = Self::from_int(Int::MAX);
+
+    #[inline]
+    pub const fn from_int(value: Int) -> Self {
+        Self {
+            whole: value,
+            rem: 0,
+            div: 1,
+        }
+    }
+
+    // `whole + rem / div`, given `rem < div`
+    const fn from_parts(whole: Int, rem: Int, div: Int) -> Self {
+        let gcd = gcd(rem, div);
+        Self {
+            whole,
+            rem: rem / gcd,
+            div: div / gcd,
+        }
+    }
+
+    /// The whole part, rounded towards zero
+    #[inline]
+    pub const fn whole(&self) -> Int {
+        self.whole
+    }
+
+    #[inline]
+    pub const fn trunc(&self) -> Self {
+        Self::from_int(self.whole)
+    }
+
+    #[inline]
+    pub const fn fract(&self) -> Self {
+        Self { whole: 0, ..*self }
+    }
+
+    #[inline]
+    pub const fn is_zero(&self) -> bool {
+        self.whole == 0 && self.rem == 0
+    }
+
+    #[inline]
+    pub const fn is_one(&self) -> bool {
+        self.whole == 1 && self.rem == 0
+    }
+
+    fn checked_mul_int(&self, rhs: Int) -> Option<Self> {
+        let (carry, rem) = mul_div(self.rem, rhs, self.div)?;
+        let whole = self.whole.checked_mul(rhs)?.checked_add(carry)?;
+        Some(Self::from_parts(whole, rem, self.div))
+    }
+
+    fn checked_div_int(&self, rhs: Int) -> Option<Self> {
+        let (whole, carry) = (self.whole.checked_div(rhs)?, self.whole % rhs);
+        // (carry + rem / div) / rhs
+        Some(match self.div.checked_mul(rhs) {
+            Some(div) => Self::from_parts(whole, carry * self.div + self.rem, div),
+            None => {
+                let (rem, left) = mul_div(carry, self.div, rhs)?;
+                // both leftovers are below `rhs`, so together they make up at most one more
+                let extra = left
+                    .checked_add(self.rem % rhs)
+                    .map_or(1, |left| left / rhs);
+                Self::from_parts(whole, rem + self.rem / rhs + extra, self.div)
+            }
+        })
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        let whole = self.whole.checked_add(rhs.whole)?;
+        let gcd = gcd(self.div, rhs.div);
+        let div = (self.div / gcd).checked_mul(rhs.div).unwrap_or(self.div);
+        let (lhs, _) = mul_div(self.rem, div, self.div)?;
+        let (rhs, _) = mul_div(rhs.rem, div, rhs.div)?;
+        // both are below `div`, so the sum carries at most once
+        Some(match lhs.checked_sub(div - rhs) {
+            Some(rem) => Self::from_parts(whole.checked_add(1)?, rem, div),
+            None => Self::from_parts(whole, lhs + rhs, div),
+        })
+    }
+
+    /// Multiplies, or returns `None` if the product overflows [`Int`]
+    pub fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        let fract = match self.checked_mul_int(rhs.rem) {
+            Some(value) => value.checked_div_int(rhs.div),
+            None => self.checked_div_int(rhs.div)?.checked_mul_int(rhs.rem),
+        }?;
+        self.checked_mul_int(rhs.whole)?.checked_add(&fract)
+    }
+
+    /// Divides, or returns `None` if `rhs` is zero or the quotient overflows [`Int`]
+    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        if rhs.rem == 0 {
+            return self.checked_div_int(rhs.whole);
+        }
+        // self / (whole + rem / div) = (self * div) / (whole * div + rem)
+        match rhs
+            .whole
+            .checked_mul(rhs.div)
+            .and_then(|whole| whole.checked_add(rhs.rem))
+        {
+            Some(div) => match self.checked_mul_int(rhs.div) {
+                Some(value) => value.checked_div_int(div),
+                None => self.checked_div_int(div)?.checked_mul_int(rhs.div),
+            },
+            // the remainder is too small to matter next to such a divisor
+            None => self.checked_div_int(rhs.whole),
+        }
+    }
+
+    // writes out the number, with up to `precision` digits after the point,
+    // rounding down and dropping trailing zeros unless `pad` is set
+    fn write_to(
+        &self,
+        w: &mut impl fmt::Write,
+        precision: Option<usize>,
+        pad: bool,
+    ) -> fmt::Result {
+        write!(w, "{}", self.whole)?;
+        // any divisor made of 2s and 5s runs out of digits within `Int::BITS`
+        let (mut rem, mut zeros, mut point) = (self.rem, 0, false);
+        for _ in 0..precision.unwrap_or(Int::BITS as usize) {
+            if rem == 0 && !pad {
+                break;
+            }
+            let (digit, next) = match mul_div(rem, 10, self.div) {
+                Some(parts) => parts,
+                None => unreachable!("remainder must be below its divisor"),
+            };
+            rem = next;
+            if digit == 0 && !pad {
+                zeros += 1;
+                continue;
+            }
+            if !point {
+                w.write_char('.')?;
+                point = true;
+            }
+            for _ in 0..zeros {
+                w.write_char('0')?;
+            }
+            zeros = 0;
+            w.write_char((b'0' + digit as u8) as char)?;
+        }
+        Ok(())
+    }
+}
+
+// counts the bytes written, to pad ahead of time
+struct Count(usize);
+
+impl fmt::Write for Count {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (precision, pad) = (f.precision(), f.alternate());
+        let Some(width) = f.width() else {
+            return self.write_to(f, precision, pad);
+        };
+        let mut len = Count(0);
+        self.write_to(&mut len, precision, pad)?;
+        let fill = width.saturating_sub(len.0);
+        let (before, after) = match f.align() {
+            Some(fmt::Alignment::Left) => (0, fill),
+            Some(fmt::Alignment::Center) => (fill / 2, fill - fill / 2),
+            Some(fmt::Alignment::Right) | None => (fill, 0),
+        };
+        for _ in 0..before {
+            f.write_char(f.fill())?;
+        }
+        self.write_to(f, precision, pad)?;
+        for _ in 0..after {
+            f.write_char(f.fill())?;
+        }
+        Ok(())
+    }
+}
+
+// builds up a decimal from its digits, keeping as many fractional digits as the divisor can hold
+struct Digits {
+    whole: Option<Int>,
+    rem: Int,
+    div: Int,
+    point: bool,
+    empty: bool,
+}
+
+impl Digits {
+    const fn new() -> Self {
+        Self {
+            whole: Some(0),
+            rem: 0,
+            div: 1,
+            point: false,
+            empty: true,
+        }
+    }
+
+    fn finish(self) -> Result<Decimal, ParseError> {
+        match (self.empty, self.whole) {
+            (true, _) => Err(ParseError::InvalidValue),
+            (_, None) => Err(ParseError::ValueOverflow),
+            (_, Some(whole)) => Ok(Decimal::from_parts(whole, self.rem, self.div)),
+        }
+    }
+}
+
+impl fmt::Write for Digits {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            let digit = match c {
+                '.' if !self.point => {
+                    self.point = true;
+                    continue;
+                }
+                c => c.to_digit(10).ok_or(fmt::Error)? as Int,
+            };
+            self.empty = false;
+            if !self.point {
+                self.whole = self
+                    .whole
+                    .and_then(|whole| whole.checked_mul(10)?.checked_add(digit));
+            } else if let Some(div) = self.div.checked_mul(10) {
+                (self.rem, self.div) = (self.rem * 10 + digit, div);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseError::EmptyInput);
+        }
+        let mut digits = Digits::new();
+        digits
+            .write_str(s.strip_prefix('+').unwrap_or(s))
+            .map_err(|_| ParseError::InvalidValue)?;
+        digits.finish()
+    }
+}
+
+macro_rules! impl_from_int {
+    (unsigned $($t:ty)+) => {
+        $(
+            impl From<$t> for Decimal {
+                #[inline]
+                fn from(value: $t) -> Self {
+                    Self::from_int(value as Int)
+                }
+            }
+        )+
+    };
+    // sizes are never negative, anything below zero is taken as zero
+    (signed $($t:ty)+) => {
+        $(
+            impl From<$t> for Decimal {
+                #[inline]
+                fn from(value: $t) -> Self {
+                    Self::from_int(value.max(0) as Int)
+                }
+            }
+        )+
+    };
+}
+
+impl_from_int!(unsigned u8 u16 u32 u64 usize);
+impl_from_int!(signed i8 i16 i32 i64 isize);
+impl_from_int!(unsigned u128);
+impl_from_int!(signed i128);
+
+macro_rules! impl_from_float {
+    ($($t:ty)+) => {
+        $(
+            // the shortest decimal that reads back as the same float, so `1.2` is exactly `12 / 10`
+            impl From<$t> for Decimal {
+                fn from(value: $t) -> Self {
+                    if value.is_nan() || value <= 0.0 {
+                        return Self::ZERO;
+                    }
+                    let mut digits = Digits::new();
+                    match write!(digits, "{}", value).map(|_| digits.finish()) {
+                        Ok(Ok(value)) => value,
+                        _ => Self::MAX,
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_from_float!(f32 f64);
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.whole
+            .cmp(&other.whole)
+            .then_with(|| widening_mul(self.rem, other.div).cmp(&widening_mul(other.rem, self.div)))
+    }
+}
+
+impl PartialOrd for Decimal {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl ops::Mul for Decimal {
+    type Output = Self;
+
+    /// # Panics
+    ///
+    /// If the product overflows [`Int`], see [`Decimal::checked_mul`].
+    fn mul(self, rhs: Self) -> Self {
+        self.checked_mul(&rhs)
+            .expect("attempt to multiply with overflow")
+    }
+}
+
+impl ops::Div for Decimal {
+    type Output = Self;
+
+    /// # Panics
+    ///
+    /// If `rhs` is zero or the quotient overflows [`Int`], see [`Decimal::checked_div`].
+    fn div(self, rhs: Self) -> Self {
+        if rhs.is_zero() {
+            panic!("attempt to divide by zero")
+        }
+        self.checked_div(&rhs)
+            .expect("attempt to divide with overflow")
+    }
+}
+
+impl ops::MulAssign for Decimal {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
+
+impl ops::DivAssign for Decimal {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn wide_arithmetic() {
+        assert_eq!((0, 6), widening_mul(2, 3));
+        assert_eq!((Int::MAX - 1, 1), widening_mul(Int::MAX, Int::MAX));
+        assert_eq!(Some((Int::MAX / 3, 0)), mul_div(Int::MAX, 2, 6));
+        assert_eq!(Some((Int::MAX, 0)), mul_div(Int::MAX, Int::MAX, Int::MAX));
+        assert_eq!(Some((Int::MAX / 2, 4)), mul_div(Int::MAX, 4, 8));
+        assert_eq!(
+            Some((Int::MAX - 1, 0)),
+            mul_div(Int::MAX, Int::MAX - 1, Int::MAX)
+        );
+        assert_eq!(None, mul_div(Int::MAX, 3, 2));
+        assert_eq!(12, gcd(36, 120));
+        assert_eq!(7, gcd(0, 7));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Decimal::from_parts(1, 1, 2), d("1.5"));
+        assert_eq!(Decimal::from_parts(1, 1, 2), d("+1.50000"));
+        assert_eq!(Decimal::from_parts(0, 1, 2), d(".5"));
+        assert_eq!(Decimal::from_int(1), d("1."));
+        assert_eq!(Decimal::from_parts(58375, 71, 250), d("58375.284"));
+        assert_eq!(Err(ParseError::EmptyInput), "".parse::<Decimal>());
+        assert_eq!(Err(ParseError::InvalidValue), ".".parse::<Decimal>());
+        assert_eq!(Err(ParseError::InvalidValue), "-1".parse::<Decimal>());
+        assert_eq!(Err(ParseError::InvalidValue), "1.2.3".parse::<Decimal>());
+        assert_eq!(Err(ParseError::InvalidValue), "1e3".parse::<Decimal>());
+        assert_eq!(
+            Err(ParseError::ValueOverflow),
+            "999999999999999999999999999999999999999999".parse::<Decimal>()
+        );
+    }
+
+    #[test]
+    fn from_primitives() {
+        assert_eq!(d("1.2"), Decimal::from(1.2));
+        assert_eq!(d("104.5"), Decimal::from(104.5_f32));
+        assert_eq!(d("0.1"), Decimal::from(0.1));
+        assert_eq!(Decimal::ZERO, Decimal::from(-3.0));
+        assert_eq!(Decimal::ZERO, Decimal::from(f64::NAN));
+        assert_eq!(Decimal::MAX, Decimal::from(f64::INFINITY));
+        assert_eq!(Decimal::ZERO, Decimal::from(-3));
+        assert_eq!(Decimal::from_int(42), Decimal::from(42_u8));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let kib = Decimal::from(1024);
+        // repeated division keeps every digit
+        let mut value = Decimal::from_int(1536 * 1024 * 1024 + 1);
+        value /= kib;
+        value /= kib;
+        value /= kib;
+        assert_eq!(Decimal::from_parts(1, 536870913, 1073741824), value);
+        assert_eq!(
+            Decimal::from_int(1536 * 1024 * 1024 + 1),
+            value * kib * kib * kib
+        );
+
+        assert_eq!(d("0.125"), Decimal::ONE / Decimal::from(8));
+        assert_eq!(d("3.75"), d("1.5") * d("2.5"));
+        assert_eq!(d("0.6"), d("1.5") / d("2.5"));
+        assert_eq!(
+            Decimal::from_parts(0, 1, 3),
+            Decimal::ONE / Decimal::from(3)
+        );
+        assert_eq!(d("5.5"), d("1.5").checked_add(&d("4")).unwrap());
+        assert_eq!(d("1.25"), d("0.75").checked_add(&d("0.5")).unwrap());
+
+        assert_eq!(None, Decimal::MAX.checked_mul(&d("1.5")));
+        assert_eq!(None, Decimal::ONE.checked_div(&Decimal::ZERO));
+        assert_eq!(Some(Decimal::MAX), Decimal::MAX.checked_mul(&Decimal::ONE));
+    }
+
+    #[test]
+    fn overflowing_divisors() {
+        // a divisor past `Int::MAX` keeps the one it has, rounding the remainder down
+        let div = 1 << (Int::BITS - 2);
+        assert_eq!(
+            Decimal::from_parts(0, 125, div),
+            Decimal::from_parts(0, 1001, div) / Decimal::from(8)
+        );
+        assert_eq!(
+            Decimal::from_parts(2, 125, div),
+            Decimal::from_parts(16, 1001, div) / Decimal::from(8)
+        );
+        let tiny = Decimal::from_parts(0, 1, Int::MAX / 2);
+        assert_eq!(Decimal::ZERO, tiny / Decimal::from_int(Int::MAX));
+    }
+
+    #[test]
+    fn ordering() {
+        assert!(d("1.5") < d("1.75"));
+        assert!(d("2") > d("1.999999"));
+        assert!(Decimal::from_parts(0, 1, 3) > d("0.333333"));
+        assert_eq!(Ordering::Equal, d("0.50").cmp(&d(".5")));
+    }
+
+    #[test]
+    fn display() {
+        let value = d("2.7234");
+        assert_eq!("2.7234", format!("{}", value));
+        assert_eq!("2.72", format!("{:.2}", value));
+        assert_eq!("2.7", format!("{:.1}", d("2.7034")));
+        assert_eq!("2.7000", format!("{:#.4}", d("2.7")));
+        assert_eq!("2", format!("{:.3}", d("2.0004")));
+        assert_eq!("2.000", format!("{:#.3}", d("2.0004")));
+        assert_eq!(
+            "0.0009765625",
+            format!("{}", Decimal::ONE / Decimal::from(1024))
+        );
+        assert_eq!("  1.5", format!("{:>5}", d("1.5")));
+        assert_eq!("1.5__", format!("{:_<5}", d("1.5")));
+        assert_eq!("-1.5-", format!("{:-^5}", d("1.5")));
+    }
+}
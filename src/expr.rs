@@ -0,0 +1,435 @@
+use super::{bytesize::parse_radix, ByteSize, ParseError};
+use core::{fmt, ops::Range};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ExprErrorKind {
+    /// a size or number that doesn't parse, or a result out of range
+    Parse(ParseError),
+    UnexpectedToken,
+    /// sizes only add to and subtract from sizes, and multiply with numbers
+    MismatchedOperands,
+    DivisionByZero,
+    /// a result below zero, where a [`ByteSize`] was asked for
+    Negative,
+}
+
+impl fmt::Display for ExprErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprErrorKind::Parse(err) => fmt::Display::fmt(err, f),
+            ExprErrorKind::UnexpectedToken => f.pad("unexpected token"),
+            ExprErrorKind::MismatchedOperands => {
+                f.pad("mismatched operands: expected a size and a number, or two sizes")
+            }
+            ExprErrorKind::DivisionByZero => f.pad("division by zero"),
+            ExprErrorKind::Negative => f.pad("negative size"),
+        }
+    }
+}
+
+/// An expression that failed to evaluate, and the byte range of the token at fault
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExprError {
+    kind: ExprErrorKind,
+    span: Range<usize>,
+}
+
+impl ExprError {
+    const fn new(kind: ExprErrorKind, span: Range<usize>) -> Self {
+        Self { kind, span }
+    }
+
+    pub const fn kind(&self) -> ExprErrorKind {
+        self.kind
+    }
+
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}..{}", self.kind, self.span.start, self.span.end)
+    }
+}
+
+impl core::error::Error for ExprError {}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Token {
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Open,
+    Close,
+    Number(i128),
+    Size(i128),
+    End,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Value {
+    Number(i128),
+    // in bytes
+    Size(i128),
+}
+
+// recursive descent, one token of lookahead
+//   expr  := term (('+' | '-') term)*
+//   term  := unary (('*' | '/') unary)*
+//   unary := '-' unary | '(' expr ')' | number | size
+struct Parser<'a> {
+    s: &'a str,
+    cursor: usize,
+    peeked: Option<(Token, Range<usize>)>,
+}
+
+impl<'a> Parser<'a> {
+    const fn new(s: &'a str) -> Self {
+        Self {
+            s,
+            cursor: 0,
+            peeked: None,
+        }
+    }
+
+    fn scan(&mut self, matches: impl Fn(char) -> bool) -> usize {
+        self.cursor += self.s[self.cursor..]
+            .find(|c| !matches(c))
+            .unwrap_or(self.s.len() - self.cursor);
+        self.cursor
+    }
+
+    fn lex(&mut self) -> Result<(Token, Range<usize>), ExprError> {
+        let start = self.scan(char::is_whitespace);
+        let Some(c) = self.s[start..].chars().next() else {
+            return Ok((Token::End, start..start));
+        };
+        let token = match c {
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            c if c.is_ascii_digit() || c == '.' => {
+                let value_end = match parse_radix(&self.s[start..]) {
+                    Ok(Some((_, rest))) => {
+                        self.cursor = self.s.len() - rest.len();
+                        self.cursor
+                    }
+                    Ok(None) => self.scan(|c| c.is_ascii_digit() || matches!(c, '.' | ',')),
+                    Err(err) => {
+                        let end =
+                            self.scan(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | ','));
+                        return Err(ExprError::new(ExprErrorKind::Parse(err), start..end));
+                    }
+                };
+                let unit_start = self.scan(char::is_whitespace);
+                let end = self.scan(char::is_alphabetic);
+                if unit_start == end {
+                    self.cursor = value_end;
+                    let number = &self.s[start..value_end];
+                    let number = match parse_radix(number) {
+                        Ok(Some((number, ""))) => i128::try_from(number).ok(),
+                        _ => number.parse().ok(),
+                    }
+                    .ok_or_else(|| {
+                        ExprError::new(
+                            ExprErrorKind::Parse(ParseError::InvalidValue),
+                            start..value_end,
+                        )
+                    })?;
+                    return Ok((Token::Number(number), start..value_end));
+                }
+                let size = self.s[start..end]
+                    .parse::<ByteSize<u128>>()
+                    .and_then(|size| {
+                        i128::try_from(size.bytes()).map_err(|_| ParseError::ValueOverflow)
+                    })
+                    .map_err(|err| ExprError::new(ExprErrorKind::Parse(err), start..end))?;
+                return Ok((Token::Size(size), start..end));
+            }
+            c if c.is_alphabetic() => {
+                let end = self.scan(char::is_alphabetic);
+                return Err(ExprError::new(
+                    ExprErrorKind::Parse(ParseError::MissingValue),
+                    start..end,
+                ));
+            }
+            c => {
+                return Err(ExprError::new(
+                    ExprErrorKind::UnexpectedToken,
+                    start..start + c.len_utf8(),
+                ))
+            }
+        };
+        self.cursor += 1;
+        Ok((token, start..self.cursor))
+    }
+
+    fn peek(&mut self) -> Result<Token, ExprError> {
+        if let Some((token, _)) = self.peeked {
+            return Ok(token);
+        }
+        let (token, span) = self.lex()?;
+        self.peeked = Some((token, span));
+        Ok(token)
+    }
+
+    fn next(&mut self) -> Result<(Token, Range<usize>), ExprError> {
+        match self.peeked.take() {
+            Some(token) => Ok(token),
+            None => self.lex(),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Value, ExprError> {
+        let mut lhs = self.term()?;
+        while let Token::Plus | Token::Minus = self.peek()? {
+            let (op, span) = self.next()?;
+            lhs = apply(lhs, op, self.term()?, span)?;
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<Value, ExprError> {
+        let mut lhs = self.unary()?;
+        while let Token::Star | Token::Slash = self.peek()? {
+            let (op, span) = self.next()?;
+            lhs = apply(lhs, op, self.unary()?, span)?;
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Value, ExprError> {
+        match self.next()? {
+            (Token::Minus, span) => {
+                let value = self.unary()?;
+                let zero = match value {
+                    Value::Number(_) => Value::Number(0),
+                    Value::Size(_) => Value::Size(0),
+                };
+                apply(zero, Token::Minus, value, span)
+            }
+            (Token::Open, _) => {
+                let value = self.expr()?;
+                match self.next()? {
+                    (Token::Close, _) => Ok(value),
+                    (_, span) => Err(ExprError::new(ExprErrorKind::UnexpectedToken, span)),
+                }
+            }
+            (Token::Number(number), _) => Ok(Value::Number(number)),
+            (Token::Size(size), _) => Ok(Value::Size(size)),
+            (_, span) => Err(ExprError::new(ExprErrorKind::UnexpectedToken, span)),
+        }
+    }
+}
+
+fn apply(lhs: Value, op: Token, rhs: Value, span: Range<usize>) -> Result<Value, ExprError> {
+    use Value::{Number, Size};
+
+    let err = |kind| ExprError::new(kind, span.clone());
+    let overflow = || err(ExprErrorKind::Parse(ParseError::ValueOverflow));
+    match (lhs, op, rhs) {
+        (_, Token::Slash, Number(0) | Size(0)) => Err(err(ExprErrorKind::DivisionByZero)),
+        (Number(a), Token::Plus, Number(b)) => a.checked_add(b).map(Number).ok_or_else(overflow),
+        (Size(a), Token::Plus, Size(b)) => a.checked_add(b).map(Size).ok_or_else(overflow),
+        (Number(a), Token::Minus, Number(b)) => a.checked_sub(b).map(Number).ok_or_else(overflow),
+        (Size(a), Token::Minus, Size(b)) => a.checked_sub(b).map(Size).ok_or_else(overflow),
+        (Number(a), Token::Star, Number(b)) => a.checked_mul(b).map(Number).ok_or_else(overflow),
+        (Size(a), Token::Star, Number(b)) | (Number(a), Token::Star, Size(b)) => {
+            a.checked_mul(b).map(Size).ok_or_else(overflow)
+        }
+        (Number(a), Token::Slash, Number(b)) | (Size(a), Token::Slash, Size(b)) => {
+            a.checked_div(b).map(Number).ok_or_else(overflow)
+        }
+        (Size(a), Token::Slash, Number(b)) => a.checked_div(b).map(Size).ok_or_else(overflow),
+        _ => Err(err(ExprErrorKind::MismatchedOperands)),
+    }
+}
+
+fn eval(s: &str) -> Result<i128, ExprError> {
+    if s.trim().is_empty() {
+        return Err(ExprError::new(
+            ExprErrorKind::Parse(ParseError::EmptyInput),
+            0..s.len(),
+        ));
+    }
+    let mut parser = Parser::new(s);
+    let value = parser.expr()?;
+    match parser.next()? {
+        (Token::End, _) => {}
+        (_, span) => return Err(ExprError::new(ExprErrorKind::UnexpectedToken, span)),
+    }
+    match value {
+        Value::Size(bytes) => Ok(bytes),
+        Value::Number(_) => Err(ExprError::new(
+            ExprErrorKind::Parse(ParseError::MissingUnit),
+            0..s.len(),
+        )),
+    }
+}
+
+impl ByteSize {
+    /// Evaluates an expression of sizes, like `3 * 1 GiB - 100 MB`
+    ///
+    /// Takes `+`, `-`, `*` and `/` with the usual precedence, parentheses, whole numbers and
+    /// sizes as [`FromStr`](core::str::FromStr) parses them, but a number needs a unit to be a size.
+    /// Sizes add to and subtract from sizes, and multiply with or divide by numbers.
+    /// Division rounds down to whole bytes, and a size divided by a size is a number.
+    ///
+    /// ```
+    /// use xbytes::{prelude::*, ExprErrorKind};
+    ///
+    /// let size = ByteSize::eval("3 * 1 GiB - 100 MB").unwrap();
+    /// assert_eq!(size, ByteSize::of(3, GIBI_BYTE) - ByteSize::of(100, MEGA_BYTE));
+    /// assert_eq!(ByteSize::eval("(1 GiB + 512 MiB) / 2"), Ok(ByteSize::of(768, MEBI_BYTE)));
+    ///
+    /// let err = ByteSize::eval("1 GiB + 2 XB").unwrap_err();
+    /// assert_eq!(err.span(), 8..12);
+    /// assert_eq!(err.to_string(), "invalid prefix at 8..12");
+    /// assert_eq!(ByteSize::eval("1 GiB - 2 GiB").unwrap_err().kind(), ExprErrorKind::Negative);
+    /// ```
+    pub fn eval(expr: &str) -> Result<Self, ExprError> {
+        let bytes = eval(expr)?;
+        if bytes < 0 {
+            return Err(ExprError::new(ExprErrorKind::Negative, 0..expr.len()));
+        }
+        u64::try_from(bytes).map(ByteSize).map_err(|_| {
+            ExprError::new(
+                ExprErrorKind::Parse(ParseError::ValueOverflow),
+                0..expr.len(),
+            )
+        })
+    }
+
+    /// Evaluates an expression of sizes to a signed number of bytes
+    ///
+    /// See [`ByteSize::eval`], except results may be negative, as with the difference of two sizes.
+    ///
+    /// ```
+    /// use xbytes::ByteSize;
+    ///
+    /// assert_eq!(ByteSize::eval_signed("1 GB - 1 GiB"), Ok(-73741824));
+    /// ```
+    pub fn eval_signed(expr: &str) -> Result<i128, ExprError> {
+        eval(expr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eval_signed() {
+        let map = [
+            ("1 GiB + 512 MiB", Ok(1610612736)),
+            ("2 * 4 KiB", Ok(8192)),
+            ("4 KiB * 2", Ok(8192)),
+            ("3 * 1 GiB - 100 MB", Ok(3121225472)),
+            ("1 KiB - 2 KiB", Ok(-1024)),
+            ("-1 KiB + 3 KiB", Ok(2048)),
+            ("2 * (1 KiB + 1 KB)", Ok(4048)),
+            ("2 * 1 KiB + 1 KB", Ok(3048)),
+            ("10 KiB / 3", Ok(3413)),
+            ("1 MiB / 1 KiB * 1 B", Ok(1024)),
+            ("1.5KiB+1,024 B", Ok(2560)),
+            ("((1 B))", Ok(1)),
+            ("0x10 * 0x1000 B", Ok(65536)),
+            ("0x1000 B + 0b10 KiB", Ok(6144)),
+            (
+                "0x1000B + 1 B",
+                Err((ExprErrorKind::MismatchedOperands, 8..9)),
+            ),
+            ("0x1000 B + 1 B", Ok(4097)),
+            ("0x1b * 1 B", Ok(27)),
+            ("0x1_b B - 0xb B", Ok(16)),
+            (
+                "0x1_ * 1 B",
+                Err((ExprErrorKind::Parse(ParseError::InvalidValue), 0..4)),
+            ),
+            (
+                "2 * 0x1__2 B",
+                Err((ExprErrorKind::Parse(ParseError::InvalidValue), 4..10)),
+            ),
+            ("0o10 KiB / 0b10", Ok(4096)),
+            (
+                "",
+                Err((ExprErrorKind::Parse(ParseError::EmptyInput), 0..0)),
+            ),
+            ("1 GiB + -", Err((ExprErrorKind::UnexpectedToken, 9..9))),
+            ("- 2 * 1 KiB", Ok(-2048)),
+            (
+                "2 * 4",
+                Err((ExprErrorKind::Parse(ParseError::MissingUnit), 0..5)),
+            ),
+            ("1 GiB +", Err((ExprErrorKind::UnexpectedToken, 7..7))),
+            ("1 GiB 2 GiB", Err((ExprErrorKind::UnexpectedToken, 6..11))),
+            ("(1 GiB", Err((ExprErrorKind::UnexpectedToken, 6..6))),
+            ("1 GiB)", Err((ExprErrorKind::UnexpectedToken, 5..6))),
+            ("1 GiB % 2", Err((ExprErrorKind::UnexpectedToken, 6..7))),
+            ("1 GiB + 2", Err((ExprErrorKind::MismatchedOperands, 6..7))),
+            (
+                "1 GiB * 1 GiB",
+                Err((ExprErrorKind::MismatchedOperands, 6..7)),
+            ),
+            ("2 / 1 GiB", Err((ExprErrorKind::MismatchedOperands, 2..3))),
+            (
+                "1 GiB / (2 - 2)",
+                Err((ExprErrorKind::DivisionByZero, 6..7)),
+            ),
+            (
+                "1.5 * 1 GiB",
+                Err((ExprErrorKind::Parse(ParseError::InvalidValue), 0..3)),
+            ),
+            (
+                "1 GiB + GiB",
+                Err((ExprErrorKind::Parse(ParseError::MissingValue), 8..11)),
+            ),
+            (
+                "1 GiB + 2 XB",
+                Err((ExprErrorKind::Parse(ParseError::InvalidPrefix), 8..12)),
+            ),
+            (
+                "1 YiB * 1 YiB / 1 YiB",
+                Err((ExprErrorKind::MismatchedOperands, 6..7)),
+            ),
+            (
+                "1 YiB * 1000000000000000",
+                Err((ExprErrorKind::Parse(ParseError::ValueOverflow), 6..7)),
+            ),
+        ];
+
+        for (expr, result) in map {
+            assert_eq!(
+                result
+                    .clone()
+                    .map_err(|(kind, span)| ExprError::new(kind, span)),
+                ByteSize::eval_signed(expr),
+                "expected [{}] to evaluate as {:?}",
+                expr,
+                result
+            );
+        }
+    }
+
+    #[test]
+    fn eval() {
+        assert_eq!(Ok(ByteSize(1536)), ByteSize::eval("1 KiB + 512 B"));
+        assert_eq!(
+            Err(ExprError::new(ExprErrorKind::Negative, 0..13)),
+            ByteSize::eval("1 KiB - 2 KiB")
+        );
+        assert_eq!(
+            Err(ExprError::new(
+                ExprErrorKind::Parse(ParseError::ValueOverflow),
+                0..13
+            )),
+            ByteSize::eval("16 EiB * 1024")
+        );
+    }
+}
@@ -0,0 +1,440 @@
+//! A `numfmt`-like filter converting sizes embedded in lines of text
+
+use super::{bytesize::ReprConfig, ByteSize, Int, Mode, ParseError, ReprFormat};
+use std::{
+    fmt,
+    io::{self, BufRead, Write},
+    str::FromStr,
+};
+
+#[derive(Eq, Copy, Clone, Debug, PartialEq)]
+pub enum Conversion {
+    /// Byte counts to human readable sizes (`1048576` -> `1 MiB`)
+    Humanize,
+    /// Human readable sizes to byte counts (`1 MiB` -> `1048576`)
+    Dehumanize,
+}
+
+/// What to do with fields that can't be converted
+#[derive(Eq, Copy, Clone, Debug, PartialEq)]
+pub enum InvalidPolicy {
+    /// Stop at the first invalid field
+    Fail,
+    /// Report invalid fields, leaving them unchanged
+    Warn,
+    /// Silently leave invalid fields unchanged
+    Ignore,
+}
+
+impl FromStr for InvalidPolicy {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Err(ParseError::EmptyInput),
+            "fail" => Ok(InvalidPolicy::Fail),
+            "warn" => Ok(InvalidPolicy::Warn),
+            "ignore" => Ok(InvalidPolicy::Ignore),
+            _ => Err(ParseError::InvalidValue),
+        }
+    }
+}
+
+/// A selection of 1-based field indices, like `1`, `2,4`, `3-5` or `2-`
+#[derive(Eq, Clone, Debug, PartialEq)]
+pub struct Fields(Vec<(usize, usize)>);
+
+impl Fields {
+    pub fn single(index: usize) -> Self {
+        Fields(vec![(index, index)])
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.0
+            .iter()
+            .any(|&(start, end)| start <= index && index <= end)
+    }
+}
+
+impl FromStr for Fields {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseError::EmptyInput);
+        }
+        let index = |s: &str| match s.parse() {
+            Ok(0) | Err(_) => Err(ParseError::InvalidValue),
+            Ok(index) => Ok(index),
+        };
+        s.split(',')
+            .map(|range| match range.split_once('-') {
+                None => index(range).map(|index| (index, index)),
+                Some(("", "")) => Err(ParseError::InvalidValue),
+                Some((start, end)) => Ok((
+                    if start.is_empty() { 1 } else { index(start)? },
+                    if end.is_empty() {
+                        usize::MAX
+                    } else {
+                        index(end)?
+                    },
+                )),
+            })
+            .collect::<Result<_, _>>()
+            .map(Fields)
+    }
+}
+
+/// A field that couldn't be converted
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvalidField {
+    pub line: usize,
+    pub field: usize,
+    pub input: String,
+    pub error: ParseError,
+}
+
+impl fmt::Display for InvalidField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, field {}: {} ({:?})",
+            self.line, self.field, self.error, self.input
+        )
+    }
+}
+
+impl std::error::Error for InvalidField {}
+
+/// Converts selected fields of each line, preserving everything else
+///
+/// ```
+/// use xbytes::{filter::Filter, Mode};
+///
+/// let filter = Filter::new().with_fields("2".parse().unwrap()).with_mode(Mode::Decimal);
+/// let (mut output, mut warnings) = (vec![], vec![]);
+/// filter
+///     .run("a.iso  4700000000  dvd\n".as_bytes(), &mut output, &mut warnings)
+///     .unwrap();
+/// assert_eq!(String::from_utf8(output).unwrap(), "a.iso  4.70 GB  dvd\n");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Filter {
+    conversion: Conversion,
+    fields: Fields,
+    delimiter: Option<char>,
+    header: usize,
+    padding: isize,
+    invalid: InvalidPolicy,
+    mode: Mode,
+    format: ReprFormat,
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        Self {
+            conversion: Conversion::Humanize,
+            fields: Fields::single(1),
+            delimiter: None,
+            header: 0,
+            padding: 0,
+            invalid: InvalidPolicy::Fail,
+            mode: Mode::Default,
+            format: ReprFormat::default(),
+        }
+    }
+
+    pub fn with_conversion(self, conversion: Conversion) -> Self {
+        Self { conversion, ..self }
+    }
+
+    pub fn with_fields(self, fields: Fields) -> Self {
+        Self { fields, ..self }
+    }
+
+    /// Split fields on `delimiter` instead of runs of whitespace
+    pub fn with_delimiter(self, delimiter: char) -> Self {
+        Self {
+            delimiter: Some(delimiter),
+            ..self
+        }
+    }
+
+    /// Pass the first `lines` lines through unchanged
+    pub fn with_header(self, lines: usize) -> Self {
+        Self {
+            header: lines,
+            ..self
+        }
+    }
+
+    /// Pad converted fields to `width` characters, right-aligned if
+    /// positive and left-aligned if negative
+    pub fn with_padding(self, width: isize) -> Self {
+        Self {
+            padding: width,
+            ..self
+        }
+    }
+
+    pub fn with_invalid(self, invalid: InvalidPolicy) -> Self {
+        Self { invalid, ..self }
+    }
+
+    pub fn with_mode(self, mode: Mode) -> Self {
+        Self { mode, ..self }
+    }
+
+    pub fn with(self, conf: impl ReprConfig) -> Self {
+        Self {
+            format: self.format.with(conf),
+            ..self
+        }
+    }
+
+    fn convert(&self, field: &str) -> Result<String, ParseError> {
+        let converted = match self.conversion {
+            Conversion::Humanize => {
+                let bytes = field.parse::<Int>().map_err(|_| ParseError::InvalidValue)?;
+                ByteSize::<Int>::saturating_from_bytes(bytes)
+                    .repr(self.mode)
+                    .with(self.format)
+                    .to_string()
+            }
+            Conversion::Dehumanize => match field.parse::<Int>() {
+                // already a count of bytes
+                Ok(bytes) => bytes.to_string(),
+                Err(_) => field.parse::<ByteSize<Int>>()?.whole_bytes().to_string(),
+            },
+        };
+        let width = self.padding.unsigned_abs();
+        Ok(if self.padding < 0 {
+            format!("{:<1$}", converted, width)
+        } else {
+            format!("{:>1$}", converted, width)
+        })
+    }
+
+    /// Convert the selected fields of a single line
+    ///
+    /// `line_no` is only used to report errors.
+    pub fn convert_line(&self, line: &str, line_no: usize) -> Result<String, InvalidField> {
+        self.convert_fields(line, line_no, Err)
+    }
+
+    // `invalid` decides whether to carry on, leaving an invalid field unchanged
+    fn convert_fields(
+        &self,
+        line: &str,
+        line_no: usize,
+        mut invalid: impl FnMut(InvalidField) -> Result<(), InvalidField>,
+    ) -> Result<String, InvalidField> {
+        let mut output = String::with_capacity(line.len());
+        let mut convert = |field_no: usize, field: &str, output: &mut String| {
+            if !self.fields.contains(field_no) {
+                output.push_str(field);
+                return Ok(());
+            }
+            match self.convert(field) {
+                Ok(converted) => output.push_str(&converted),
+                Err(error) => {
+                    invalid(InvalidField {
+                        line: line_no,
+                        field: field_no,
+                        input: field.to_string(),
+                        error,
+                    })?;
+                    output.push_str(field);
+                }
+            }
+            Ok(())
+        };
+        match self.delimiter {
+            Some(delimiter) => {
+                for (index, field) in line.split(delimiter).enumerate() {
+                    if index > 0 {
+                        output.push(delimiter);
+                    }
+                    convert(index + 1, field, &mut output)?;
+                }
+            }
+            None => {
+                let mut rest = line;
+                let mut field_no = 0;
+                loop {
+                    let start = rest
+                        .find(|c: char| !c.is_whitespace())
+                        .unwrap_or(rest.len());
+                    output.push_str(&rest[..start]);
+                    rest = &rest[start..];
+                    if rest.is_empty() {
+                        break;
+                    }
+                    let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                    field_no += 1;
+                    convert(field_no, &rest[..end], &mut output)?;
+                    rest = &rest[end..];
+                }
+            }
+        }
+        Ok(output)
+    }
+
+    /// Filter `input` line by line into `output`, reporting invalid fields to `warnings`
+    ///
+    /// Returns the number of invalid fields encountered. With [`InvalidPolicy::Fail`],
+    /// the first invalid field aborts with an [`io::ErrorKind::InvalidData`] error
+    /// wrapping an [`InvalidField`].
+    pub fn run(
+        &self,
+        input: impl BufRead,
+        mut output: impl Write,
+        mut warnings: impl Write,
+    ) -> io::Result<usize> {
+        let mut count = 0;
+        for (index, line) in input.lines().enumerate() {
+            let line = line?;
+            if index < self.header {
+                writeln!(output, "{}", line)?;
+                continue;
+            }
+            let mut invalid = vec![];
+            let converted = self
+                .convert_fields(&line, index + 1, |err| match self.invalid {
+                    InvalidPolicy::Fail => Err(err),
+                    _ => {
+                        invalid.push(err);
+                        Ok(())
+                    }
+                })
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            count += invalid.len();
+            if self.invalid == InvalidPolicy::Warn {
+                for err in invalid {
+                    writeln!(warnings, "{}", err)?;
+                }
+            }
+            writeln!(output, "{}", converted)?;
+        }
+        output.flush()?;
+        Ok(count)
+    }
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::Format, *};
+
+    fn run(filter: &Filter, input: &str) -> (io::Result<usize>, String, String) {
+        let (mut output, mut warnings) = (vec![], vec![]);
+        let result = filter.run(input.as_bytes(), &mut output, &mut warnings);
+        (
+            result,
+            String::from_utf8(output).unwrap(),
+            String::from_utf8(warnings).unwrap(),
+        )
+    }
+
+    #[test]
+    fn parse_fields() {
+        let fields = "1,3-4,6-".parse::<Fields>().unwrap();
+        for (index, expected) in [(1, true), (2, false), (4, true), (5, false), (99, true)] {
+            assert_eq!(expected, fields.contains(index), "field {}", index);
+        }
+        assert!("-2".parse::<Fields>().unwrap().contains(1));
+        assert_eq!(Err(ParseError::EmptyInput), "".parse::<Fields>());
+        assert_eq!(Err(ParseError::InvalidValue), "0".parse::<Fields>());
+        assert_eq!(Err(ParseError::InvalidValue), "-".parse::<Fields>());
+        assert_eq!(Err(ParseError::InvalidValue), "1,x".parse::<Fields>());
+    }
+
+    #[test]
+    fn humanize_preserves_layout() {
+        let filter = Filter::new().with_fields("2-3".parse().unwrap());
+        let (result, output, _) = run(
+            &filter,
+            "  disk   1073741824\t1536 rest of line\n\nboot 512\n",
+        );
+        assert_eq!(0, result.unwrap());
+        assert_eq!(
+            "  disk   1 GiB\t1.50 KiB rest of line\n\nboot 512 B\n",
+            output
+        );
+    }
+
+    #[test]
+    fn dehumanize_with_delimiter() {
+        let filter = Filter::new()
+            .with_conversion(Conversion::Dehumanize)
+            .with_delimiter(',')
+            .with_fields("2,3".parse().unwrap())
+            .with_header(1);
+        let (result, output, _) = run(&filter, "name,size,quota\na,1.5 KiB,1 MB\nb,42,\n");
+        assert_eq!(
+            io::ErrorKind::InvalidData,
+            result.as_ref().unwrap_err().kind()
+        );
+        assert_eq!("name,size,quota\na,1536,1000000\n", output);
+
+        let (result, output, warnings) =
+            run(&filter.with_invalid(InvalidPolicy::Warn), "x\na,1 KiB,\n");
+        assert_eq!(1, result.unwrap());
+        assert_eq!("x\na,1024,\n", output);
+        assert_eq!("line 2, field 3: empty input (\"\")\n", warnings);
+    }
+
+    #[test]
+    fn round_trip_past_u64() {
+        let (_, output, _) = run(&Filter::new(), "23058430092136939520\n");
+        assert_eq!("20 EiB\n", output);
+
+        let filter = Filter::new()
+            .with_conversion(Conversion::Dehumanize)
+            .with_delimiter(',');
+        let (result, output, _) = run(&filter, "20 EiB\n1 ZiB\n");
+        assert_eq!(0, result.unwrap());
+        assert_eq!("23058430092136939520\n1180591620717411303424\n", output);
+    }
+
+    #[test]
+    fn padding_and_format() {
+        let filter = Filter::new()
+            .with_mode(Mode::Decimal)
+            .with(Format::NoSpace)
+            .with_padding(8);
+        let (_, output, _) = run(&filter, "1500 a\n12 b\n");
+        assert_eq!("  1.50KB a\n     12B b\n", output);
+
+        let (_, output, _) = run(&filter.with_padding(-6), "1000 a\n");
+        assert_eq!("1KB    a\n", output);
+    }
+
+    #[test]
+    fn invalid_policies() {
+        let input = "1024 x 2048\n";
+        let filter = Filter::new().with_fields("1-".parse().unwrap());
+
+        let (result, output, _) = run(&filter, input);
+        let err = result.unwrap_err().into_inner().unwrap();
+        assert_eq!("line 1, field 2: invalid value (\"x\")", err.to_string());
+        assert_eq!("", output);
+
+        let (result, output, warnings) =
+            run(&filter.clone().with_invalid(InvalidPolicy::Ignore), input);
+        assert_eq!(1, result.unwrap());
+        assert_eq!("1 KiB x 2 KiB\n", output);
+        assert_eq!("", warnings);
+
+        assert_eq!(Ok(InvalidPolicy::Warn), "warn".parse());
+        assert_eq!(
+            Err(ParseError::InvalidValue),
+            "abort".parse::<InvalidPolicy>()
+        );
+    }
+}
@@ -0,0 +1,441 @@
+//! Filesystem helpers reporting sizes as [`ByteSize`]
+
+use super::{ByteSize, Int};
+use std::{
+    collections::HashSet,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Size of the file at `path`, following symlinks
+pub fn file_size(path: impl AsRef<Path>) -> io::Result<ByteSize> {
+    Ok(ByteSize::saturating_from_bytes(
+        fs::metadata(path)?.len() as Int
+    ))
+}
+
+#[derive(Eq, Copy, Clone, Debug, Default, PartialEq)]
+pub enum SizeKind {
+    /// The length of each file's contents
+    #[default]
+    Apparent,
+    /// The space allocated on disk for each file and directory (`st_blocks` on Unix)
+    ///
+    /// Falls back to the apparent size on other platforms.
+    Allocated,
+}
+
+#[derive(Eq, Copy, Clone, Debug, Default, PartialEq)]
+pub enum Symlinks {
+    /// Ignore symlinks altogether
+    #[default]
+    Skip,
+    /// Measure whatever symlinks point to
+    Follow,
+}
+
+type Callback<'a> = Box<dyn FnMut(&Path, ByteSize) + 'a>;
+
+/// A `du`-like recursive size calculator
+///
+/// Files reachable through multiple hard links or symlinks are only counted once.
+/// Entries below the measured path that can't be read are left out of the total
+/// and listed in [`DiskUsage::skipped`], only the path itself failing is an error.
+///
+/// ```no_run
+/// use xbytes::fs::{DiskUsage, SizeKind};
+///
+/// let size = DiskUsage::new()
+///     .with_kind(SizeKind::Allocated)
+///     .on_progress(|path, total| eprintln!("{} ({})", path.display(), total))
+///     .measure("/var/log")?;
+/// println!("{}", size);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct DiskUsage<'a> {
+    kind: SizeKind,
+    symlinks: Symlinks,
+    callback: Option<Callback<'a>>,
+    skipped: Vec<(PathBuf, io::Error)>,
+}
+
+impl<'a> DiskUsage<'a> {
+    pub fn new() -> Self {
+        Self {
+            kind: SizeKind::Apparent,
+            symlinks: Symlinks::Skip,
+            callback: None,
+            skipped: vec![],
+        }
+    }
+
+    pub fn with_kind(self, kind: SizeKind) -> Self {
+        Self { kind, ..self }
+    }
+
+    pub fn with_symlinks(self, symlinks: Symlinks) -> Self {
+        Self { symlinks, ..self }
+    }
+
+    /// Invoke `callback` with each measured file and the running total
+    ///
+    /// Directories are only reported when measuring [`SizeKind::Allocated`].
+    pub fn on_progress(self, callback: impl FnMut(&Path, ByteSize) + 'a) -> Self {
+        Self {
+            callback: Some(Box::new(callback)),
+            ..self
+        }
+    }
+
+    /// Total size of `path`, recursing into it if it's a directory
+    pub fn measure(&mut self, path: impl AsRef<Path>) -> io::Result<ByteSize> {
+        self.skipped.clear();
+        let mut walk = Walk {
+            usage: self,
+            seen: HashSet::new(),
+            total: 0,
+        };
+        walk.run(path.as_ref())?;
+        Ok(ByteSize::saturating_from_bytes(walk.total))
+    }
+}
+
+impl DiskUsage<'_> {
+    /// Entries the last [`measure`](DiskUsage::measure) couldn't read, and why
+    pub fn skipped(&self) -> &[(PathBuf, io::Error)] {
+        &self.skipped
+    }
+}
+
+impl Default for DiskUsage<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for DiskUsage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DiskUsage")
+            .field("kind", &self.kind)
+            .field("symlinks", &self.symlinks)
+            .field("skipped", &self.skipped)
+            .finish()
+    }
+}
+
+struct Walk<'u, 'a> {
+    usage: &'u mut DiskUsage<'a>,
+    seen: HashSet<FileId>,
+    total: Int,
+}
+
+impl Walk<'_, '_> {
+    // directories are walked with a stack of pending paths rather than recursively, so deep
+    // trees neither overflow the call stack nor hold a descriptor open per level
+    fn run(&mut self, root: &Path) -> io::Result<()> {
+        let mut pending = vec![];
+        self.visit(root, true, &mut pending)?;
+        while let Some(path) = pending.pop() {
+            // a failure below the root only leaves that entry out
+            if let Err(err) = self.visit(&path, false, &mut pending) {
+                self.usage.skipped.push((path, err));
+            }
+        }
+        Ok(())
+    }
+
+    // count `path`, queueing its entries in `pending` if it's a directory
+    fn visit(&mut self, path: &Path, root: bool, pending: &mut Vec<PathBuf>) -> io::Result<()> {
+        let mut meta = fs::symlink_metadata(path)?;
+        if meta.file_type().is_symlink() {
+            if !root && self.usage.symlinks == Symlinks::Skip {
+                return Ok(());
+            }
+            meta = fs::metadata(path)?;
+        }
+        if !self.seen.insert(FileId::of(path, &meta)?) {
+            return Ok(());
+        }
+        if meta.is_dir() {
+            // read every entry up front, closing the directory before descending into it
+            for entry in fs::read_dir(path)? {
+                match entry {
+                    Ok(entry) => pending.push(entry.path()),
+                    Err(err) => self.usage.skipped.push((path.to_path_buf(), err)),
+                }
+            }
+            // like `du`, directories take up their own blocks, but have no contents of their own
+            if self.usage.kind == SizeKind::Apparent {
+                return Ok(());
+            }
+        }
+        self.total = self.total.saturating_add(size_of(&meta, self.usage.kind));
+        if let Some(callback) = &mut self.usage.callback {
+            callback(path, ByteSize::saturating_from_bytes(self.total));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn size_of(meta: &fs::Metadata, kind: SizeKind) -> Int {
+    use std::os::unix::fs::MetadataExt;
+    match kind {
+        SizeKind::Apparent => meta.len() as Int,
+        // st_blocks is always in units of 512 bytes
+        SizeKind::Allocated => meta.blocks() as Int * 512,
+    }
+}
+
+#[cfg(not(unix))]
+fn size_of(meta: &fs::Metadata, _kind: SizeKind) -> Int {
+    meta.len() as Int
+}
+
+#[derive(Eq, Hash, PartialEq)]
+enum FileId {
+    #[cfg(unix)]
+    Inode(u64, u64),
+    #[cfg(not(unix))]
+    Path(std::path::PathBuf),
+}
+
+impl FileId {
+    #[cfg(unix)]
+    fn of(_path: &Path, meta: &fs::Metadata) -> io::Result<Self> {
+        use std::os::unix::fs::MetadataExt;
+        Ok(FileId::Inode(meta.dev(), meta.ino()))
+    }
+
+    #[cfg(not(unix))]
+    fn of(path: &Path, _meta: &fs::Metadata) -> io::Result<Self> {
+        fs::canonicalize(path).map(FileId::Path)
+    }
+}
+
+/// Capacity of the filesystem containing a path
+#[cfg(unix)]
+#[derive(Eq, Copy, Clone, Debug, PartialEq)]
+pub struct DiskSpace {
+    pub total: ByteSize,
+    /// Space not in use, including any reserved for privileged users
+    pub free: ByteSize,
+    /// Space available to unprivileged users
+    pub available: ByteSize,
+}
+
+/// Query the capacity of the filesystem containing `path`
+#[cfg(unix)]
+pub fn disk_space(path: impl AsRef<Path>) -> io::Result<DiskSpace> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let path = CString::new(path.as_ref().as_os_str().as_bytes())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: `path` is NUL-terminated and `stat` is only read after a successful call
+    let stat = unsafe {
+        if libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        stat.assume_init()
+    };
+    #[allow(clippy::unnecessary_cast)]
+    let blocks = |count| {
+        ByteSize::saturating_from_bytes((count as Int).saturating_mul(stat.f_frsize as Int))
+    };
+    Ok(DiskSpace {
+        total: blocks(stat.f_blocks),
+        free: blocks(stat.f_bfree),
+        available: blocks(stat.f_bavail),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::sizes::*, *};
+    use std::{cell::RefCell, path::PathBuf};
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("xbytes-fs-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        fn file(&self, name: &str, len: usize) -> PathBuf {
+            let path = self.0.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, vec![0; len]).unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn single_file() {
+        let dir = TempDir::new("single");
+        let file = dir.file("a", 1536);
+
+        assert_eq!(ByteSize::of(1.5, KIBI_BYTE), file_size(&file).unwrap());
+        assert_eq!(
+            ByteSize::of(1.5, KIBI_BYTE),
+            DiskUsage::new().measure(&file).unwrap()
+        );
+        assert!(file_size(dir.0.join("missing")).is_err());
+    }
+
+    #[test]
+    fn recursive_apparent_size() {
+        let dir = TempDir::new("recursive");
+        dir.file("a", 1024);
+        dir.file("b/c", 2048);
+        dir.file("b/d/e", 1024);
+
+        assert_eq!(
+            ByteSize::of(4, KIBI_BYTE),
+            DiskUsage::new().measure(&dir.0).unwrap()
+        );
+    }
+
+    #[test]
+    fn progress_callback() {
+        let dir = TempDir::new("progress");
+        dir.file("a", 100);
+        dir.file("b/c", 200);
+
+        let seen = RefCell::new(vec![]);
+        let total = DiskUsage::new()
+            .on_progress(|path, total| seen.borrow_mut().push((path.to_path_buf(), total)))
+            .measure(&dir.0)
+            .unwrap();
+
+        let mut seen = seen.into_inner();
+        seen.sort_by_key(|(_, total)| *total);
+        assert_eq!(2, seen.len());
+        assert_eq!(total, seen[1].1);
+        assert_eq!(ByteSize::of(300, BYTE), total);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn symlinks_and_hard_links() {
+        let dir = TempDir::new("links");
+        let target = dir.file("outside/big", 4096);
+        dir.file("inside/small", 1024);
+        std::os::unix::fs::symlink(&target, dir.0.join("inside/link")).unwrap();
+        fs::hard_link(dir.0.join("inside/small"), dir.0.join("inside/hard")).unwrap();
+        // a cycle, only walked when following symlinks
+        std::os::unix::fs::symlink(&dir.0, dir.0.join("inside/loop")).unwrap();
+
+        let inside = dir.0.join("inside");
+        assert_eq!(
+            ByteSize::of(1, KIBI_BYTE),
+            DiskUsage::new().measure(&inside).unwrap()
+        );
+        assert_eq!(
+            ByteSize::of(5, KIBI_BYTE),
+            DiskUsage::new()
+                .with_symlinks(Symlinks::Follow)
+                .measure(&inside)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn allocated_size() {
+        use std::os::unix::fs::MetadataExt;
+
+        let dir = TempDir::new("allocated");
+        let file = dir.file("a", 10000);
+
+        let allocated = DiskUsage::new()
+            .with_kind(SizeKind::Allocated)
+            .measure(&file)
+            .unwrap();
+        let blocks = fs::metadata(&file).unwrap().blocks();
+        assert_eq!(blocks as Int * 512, allocated.whole_bytes());
+
+        // directories count their own blocks too
+        let sub = dir.file("b/c", 10000);
+        let allocated = DiskUsage::new()
+            .with_kind(SizeKind::Allocated)
+            .measure(&dir.0)
+            .unwrap();
+        let blocks = [&dir.0, &file, &dir.0.join("b"), &sub]
+            .map(|path| fs::metadata(path).unwrap().blocks())
+            .iter()
+            .sum::<u64>();
+        assert_eq!(blocks as Int * 512, allocated.whole_bytes());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn deep_trees_with_few_descriptors() {
+        // descriptor limits are process wide, so the walk runs in a child of this test binary
+        if std::env::var_os("XBYTES_FEW_FDS").is_none() {
+            let status = std::process::Command::new(std::env::current_exe().unwrap())
+                .args(["--exact", "fs::tests::deep_trees_with_few_descriptors"])
+                .env("XBYTES_FEW_FDS", "1")
+                .status()
+                .unwrap();
+            assert!(status.success());
+            return;
+        }
+
+        let dir = TempDir::new("deep");
+        dir.file(&format!("{}a", "d/".repeat(100)), 1024);
+
+        let mut limit = std::mem::MaybeUninit::<libc::rlimit>::uninit();
+        // SAFETY: `limit` is only read after a successful call, and only the soft limit is lowered
+        unsafe {
+            assert_eq!(0, libc::getrlimit(libc::RLIMIT_NOFILE, limit.as_mut_ptr()));
+            let limit = libc::rlimit {
+                rlim_cur: 40,
+                ..limit.assume_init()
+            };
+            assert_eq!(0, libc::setrlimit(libc::RLIMIT_NOFILE, &limit));
+        }
+
+        let mut usage = DiskUsage::new();
+        assert_eq!(ByteSize::of(1, KIBI_BYTE), usage.measure(&dir.0).unwrap());
+        assert!(usage.skipped().is_empty(), "{:?}", usage.skipped());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn unreadable_entries_are_skipped() {
+        let dir = TempDir::new("unreadable");
+        dir.file("a", 1024);
+        dir.file("b/c", 2048);
+        std::os::unix::fs::symlink(dir.0.join("missing"), dir.0.join("b/dangling")).unwrap();
+
+        let mut usage = DiskUsage::new().with_symlinks(Symlinks::Follow);
+        assert_eq!(ByteSize::of(3, KIBI_BYTE), usage.measure(&dir.0).unwrap());
+        assert_eq!(1, usage.skipped().len());
+        assert_eq!(dir.0.join("b/dangling"), usage.skipped()[0].0);
+        assert_eq!(io::ErrorKind::NotFound, usage.skipped()[0].1.kind());
+
+        // the path itself failing is still an error
+        assert!(usage.measure(dir.0.join("missing")).is_err());
+        assert!(usage.skipped().is_empty());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn disk_space_of_temp_dir() {
+        let space = disk_space(std::env::temp_dir()).unwrap();
+        assert!(space.total > ByteSize::of(0, BYTE));
+        assert!(space.free <= space.total);
+        assert!(space.available <= space.free);
+    }
+}
@@ -0,0 +1,335 @@
+//! Read/Write adapters that measure transfer volumes as [`ByteSize`]
+
+use super::{
+    clock::{Clock, SystemClock},
+    ByteSize, Int, Mode,
+};
+use std::{
+    fmt,
+    io::{self, BufRead, Read, Seek, SeekFrom, Write},
+    time::{Duration, Instant},
+};
+
+/// When a counting adapter should invoke its progress callback
+#[derive(Eq, Copy, Clone, Debug, PartialEq)]
+pub enum Trigger {
+    /// Every time the total crosses a multiple of the given size
+    Every(ByteSize),
+    /// Every time the unit of the total's representation changes (e.g. KiB -> MiB)
+    UnitChange(Mode),
+}
+
+impl Trigger {
+    fn fires(&self, before: Int, after: Int) -> bool {
+        match *self {
+            Trigger::Every(step) => match step.whole_bytes() {
+                0 => false,
+                step => before / step != after / step,
+            },
+            Trigger::UnitChange(mode) => {
+                ByteSize::<Int>::saturating_from_bytes(before)
+                    .repr(mode)
+                    .unit()
+                    != ByteSize::<Int>::saturating_from_bytes(after)
+                        .repr(mode)
+                        .unit()
+            }
+        }
+    }
+}
+
+type Callback<'a> = Box<dyn FnMut(ByteSize) + 'a>;
+
+struct Counter<'a, C> {
+    clock: C,
+    total: Int,
+    started: Instant,
+    last: Instant,
+    last_rate: f64,
+    hook: Option<(Trigger, Callback<'a>)>,
+}
+
+impl<'a, C: Clock> Counter<'a, C> {
+    fn new(clock: C) -> Self {
+        let now = clock.now();
+        Self {
+            clock,
+            total: 0,
+            started: now,
+            last: now,
+            last_rate: 0.0,
+            hook: None,
+        }
+    }
+
+    fn record(&mut self, n: usize) {
+        if n == 0 {
+            return;
+        }
+        let now = self.clock.now();
+        let elapsed = now.duration_since(self.last).as_secs_f64();
+        if elapsed > 0.0 {
+            self.last_rate = n as f64 / elapsed;
+        }
+        let before = self.total;
+        self.total = self.total.saturating_add(n as Int);
+        self.last = now;
+        if let Some((trigger, callback)) = &mut self.hook {
+            if trigger.fires(before, self.total) {
+                callback(ByteSize::saturating_from_bytes(self.total))
+            }
+        }
+    }
+}
+
+impl<C> Counter<'_, C> {
+    fn elapsed(&self) -> Duration {
+        self.last.duration_since(self.started)
+    }
+
+    fn average_rate(&self) -> ByteSize {
+        match self.elapsed().as_secs_f64() {
+            secs if secs > 0.0 => per_second(self.total as f64 / secs),
+            _ => ByteSize::saturating_from_bytes(0),
+        }
+    }
+}
+
+fn per_second(bytes: f64) -> ByteSize {
+    ByteSize::saturating_from_bytes(bytes as Int)
+}
+
+impl<C> fmt::Debug for Counter<'_, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Counter")
+            .field("total", &self.total)
+            .field("elapsed", &self.elapsed())
+            .field("trigger", &self.hook.as_ref().map(|(trigger, _)| trigger))
+            .finish()
+    }
+}
+
+macro_rules! impl_counting {
+    ($name:ident) => {
+        impl<'a, T> $name<'a, T> {
+            pub fn new(inner: T) -> Self {
+                Self::with_clock(inner, SystemClock)
+            }
+        }
+
+        impl<'a, T, C: Clock> $name<'a, T, C> {
+            /// Like `new`, timing transfers with `clock` instead of the system clock
+            pub fn with_clock(inner: T, clock: C) -> Self {
+                Self {
+                    inner,
+                    counter: Counter::new(clock),
+                }
+            }
+
+            /// Invoke `callback` with the running total whenever `trigger` fires
+            pub fn on_progress(
+                mut self,
+                trigger: Trigger,
+                callback: impl FnMut(ByteSize) + 'a,
+            ) -> Self {
+                self.counter.hook = Some((trigger, Box::new(callback)));
+                self
+            }
+
+            /// Amount of data transferred so far
+            pub fn count(&self) -> ByteSize {
+                ByteSize::saturating_from_bytes(self.counter.total)
+            }
+
+            /// Time between construction and the most recent transfer
+            pub fn elapsed(&self) -> Duration {
+                self.counter.elapsed()
+            }
+
+            /// Bytes per second, as measured over the most recent transfer
+            pub fn instant_rate(&self) -> ByteSize {
+                per_second(self.counter.last_rate)
+            }
+
+            /// Bytes per second, averaged over the lifetime of the adapter
+            pub fn average_rate(&self) -> ByteSize {
+                self.counter.average_rate()
+            }
+
+            pub fn get_ref(&self) -> &T {
+                &self.inner
+            }
+
+            pub fn get_mut(&mut self) -> &mut T {
+                &mut self.inner
+            }
+
+            pub fn into_inner(self) -> T {
+                self.inner
+            }
+        }
+
+        impl<T: Seek, C> Seek for $name<'_, T, C> {
+            fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+                self.inner.seek(pos)
+            }
+        }
+    };
+}
+
+/// A [`Read`] adapter counting the bytes read through it
+#[derive(Debug)]
+pub struct CountingReader<'a, R, C = SystemClock> {
+    inner: R,
+    counter: Counter<'a, C>,
+}
+
+/// A [`Write`] adapter counting the bytes written through it
+#[derive(Debug)]
+pub struct CountingWriter<'a, W, C = SystemClock> {
+    inner: W,
+    counter: Counter<'a, C>,
+}
+
+impl_counting!(CountingReader);
+impl_counting!(CountingWriter);
+
+impl<R: Read, C: Clock> Read for CountingReader<'_, R, C> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.counter.record(n);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead, C: Clock> BufRead for CountingReader<'_, R, C> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.counter.record(amt);
+    }
+}
+
+impl<W: Write, C: Clock> Write for CountingWriter<'_, W, C> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.counter.record(n);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{clock::ManualClock, sizes::*},
+        *,
+    };
+    use std::{cell::RefCell, io::Cursor};
+
+    #[test]
+    fn counting_reader() {
+        let data = vec![0u8; 3 * 1024];
+        let mut reader = CountingReader::new(&data[..]);
+        let mut buf = [0u8; 1000];
+
+        assert_eq!(1000, reader.read(&mut buf).unwrap());
+        assert_eq!(ByteSize::of(1000, BYTE), reader.count());
+
+        io::copy(&mut reader, &mut io::sink()).unwrap();
+        assert_eq!(ByteSize::of(3, KIBI_BYTE), reader.count());
+        assert_eq!("3 KiB", reader.count().to_string());
+    }
+
+    #[test]
+    fn counting_buf_reader() {
+        let mut reader = CountingReader::new(Cursor::new("one\ntwo\nthree\n"));
+        let mut line = String::new();
+
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(ByteSize::of(4, BYTE), reader.count());
+
+        assert_eq!(2, reader.lines().count());
+    }
+
+    #[test]
+    fn counting_writer_with_seek() {
+        let mut writer = CountingWriter::new(Cursor::new(Vec::new()));
+
+        writer.write_all(&[1; 512]).unwrap();
+        writer.seek(SeekFrom::Start(0)).unwrap();
+        writer.write_all(&[2; 512]).unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(ByteSize::of(1, KIBI_BYTE), writer.count());
+        assert_eq!(512, writer.into_inner().into_inner().len());
+    }
+
+    #[test]
+    fn trigger_every() {
+        let fired = RefCell::new(vec![]);
+        let mut writer = CountingWriter::new(io::sink())
+            .on_progress(Trigger::Every(ByteSize::of(1, KIBI_BYTE)), |size| {
+                fired.borrow_mut().push(size)
+            });
+
+        for _ in 0..10 {
+            writer.write_all(&[0; 300]).unwrap();
+        }
+        drop(writer);
+
+        assert_eq!(
+            vec![ByteSize::of(1200, BYTE), ByteSize::of(2100, BYTE)],
+            fired.into_inner()
+        );
+    }
+
+    #[test]
+    fn trigger_unit_change() {
+        let fired = RefCell::new(vec![]);
+        let mut writer = CountingWriter::new(io::sink()).on_progress(
+            Trigger::UnitChange(Mode::Decimal),
+            |size| {
+                fired
+                    .borrow_mut()
+                    .push(size.repr(Mode::Decimal).to_string())
+            },
+        );
+
+        writer.write_all(&[0; 600]).unwrap();
+        writer.write_all(&[0; 600]).unwrap();
+        writer.write_all(&[0; 600]).unwrap();
+        writer.write_all(&vec![0; 1_000_000]).unwrap();
+        drop(writer);
+
+        assert_eq!(vec!["1.20 KB", "1.00 MB"], fired.into_inner());
+    }
+
+    #[test]
+    fn rates() {
+        let clock = ManualClock::new();
+        let mut writer = CountingWriter::with_clock(io::sink(), &clock);
+        assert_eq!(ByteSize::of(0, BYTE), writer.average_rate());
+        assert_eq!(ByteSize::of(0, BYTE), writer.instant_rate());
+
+        clock.advance(Duration::from_millis(500));
+        writer.write_all(&[0; 1024]).unwrap();
+
+        assert_eq!(Duration::from_millis(500), writer.elapsed());
+        assert_eq!(ByteSize::of(2, KIBI_BYTE), writer.average_rate());
+        assert_eq!(ByteSize::of(2, KIBI_BYTE), writer.instant_rate());
+
+        clock.advance(Duration::from_millis(1500));
+        writer.write_all(&[0; 1024]).unwrap();
+
+        assert_eq!(Duration::from_secs(2), writer.elapsed());
+        assert_eq!(ByteSize::of(1, KIBI_BYTE), writer.average_rate());
+        assert_eq!(ByteSize::of(682, BYTE), writer.instant_rate());
+    }
+}
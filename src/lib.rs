@@ -1,14 +1,55 @@
-use std::fmt;
+//! Convert between raw byte sizes and human readable sizes.
+//!
+//! # Upgrading from 0.1.0
+//!
+//! [`ByteSize`] and [`BitSize`] are generic over their backing integer, which defaults
+//! to `u64` where it used to be `u128`. Sizes that need the wider range are spelled
+//! `ByteSize<u128>`, and the `u128` feature no longer does anything. [`ByteSize::of`] and
+//! [`ByteSize::from_bytes`] still build the default `u64` sizes, wider ones are built with
+//! `ByteSize::of_u128(1, YOBI_BYTE)` and `ByteSize::from_u128(bytes)`, and likewise for [`BitSize`].
+//!
+//! [`ReprConfigVariant::ThousandsSeparator`] takes a `char` rather than a `&'static str`,
+//! so `ThousandsSeparator("_")` becomes `ThousandsSeparator('_')`.
+//!
+//! Parsing a size past the range of the backing integer, as in `"16 EiB".parse::<ByteSize>()`,
+//! returns [`ParseError::ValueOverflow`] where it used to saturate at the maximum.
+//!
+//! `from_bits`/`bits` on [`ByteSize`] and `from_bytes`/`bytes` on [`BitSize`] are
+//! implemented once per width so they can stay `const`, which means a call that
+//! doesn't otherwise pin the width needs it spelled out, as in `ByteSize::<u64>::from_bits(8)`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+use core::fmt;
 
-#[cfg(feature = "u128")]
 pub type Int = u128;
-#[cfg(not(feature = "u128"))]
-pub type Int = u64;
+
+// binary gcd, shared by the exact decimals and by range formatting
+pub(crate) const fn gcd(mut a: Int, mut b: Int) -> Int {
+    if a == 0 || b == 0 {
+        return a | b;
+    }
+    let shift = (a | b).trailing_zeros();
+    a >>= a.trailing_zeros();
+    loop {
+        b >>= b.trailing_zeros();
+        if a > b {
+            (a, b) = (b, a);
+        }
+        b -= a;
+        if b == 0 {
+            return a << shift;
+        }
+    }
+}
 
 #[cfg(not(feature = "lossless"))]
 pub type Float = f64;
 #[cfg(feature = "lossless")]
-pub type Float = fraction::GenericFraction<Int>;
+pub type Float = Decimal;
 
 macro_rules! f {
     ($value:expr) => {{
@@ -23,21 +64,39 @@ macro_rules! f {
 macro_rules! i {
     ($value:expr) => {{
         #[cfg(feature = "lossless")]
-        let val = if let fraction::GenericFraction::Rational(fraction::Sign::Plus, r) = $value {
-            r.numer() / r.denom()
-        } else {
-            panic!("conversion to Int failed: expected unsigned rational float")
-        };
+        let val = $value.whole();
         #[cfg(not(feature = "lossless"))]
         let val = $value as Int;
         val
     }};
 }
 
+// `f64::trunc` and `f64::fract` live in std, but sizes are never negative
+// nor past `u128::MAX`, so a round trip through `u128` truncates them just as well
+macro_rules! f_trunc {
+    ($value:expr) => {{
+        #[cfg(any(feature = "lossless", feature = "std"))]
+        let val = $value.trunc();
+        #[cfg(not(any(feature = "lossless", feature = "std")))]
+        let val = $value as u128 as Float;
+        val
+    }};
+}
+
+macro_rules! f_fract {
+    ($value:expr) => {{
+        #[cfg(any(feature = "lossless", feature = "std"))]
+        let val = $value.fract();
+        #[cfg(not(any(feature = "lossless", feature = "std")))]
+        let val = $value - f_trunc!($value);
+        val
+    }};
+}
+
 macro_rules! f_is_zero {
     ($value:expr) => {{
         #[cfg(feature = "lossless")]
-        let res = fraction::Zero::is_zero(&$value);
+        let res = $value.is_zero();
         #[cfg(not(feature = "lossless"))]
         let res = $value == 0.0;
         res
@@ -47,46 +106,32 @@ macro_rules! f_is_zero {
 macro_rules! f_is_one {
     ($value:expr) => {{
         #[cfg(feature = "lossless")]
-        let res = fraction::One::is_one(&$value);
+        let res = $value.is_one();
         #[cfg(not(feature = "lossless"))]
         let res = $value == 1.0;
         res
     }};
 }
 
-#[inline]
-#[cfg(feature = "lossless")]
-fn get_max_saturate<T: fraction::Bounded>(_value: Option<T>) -> T {
-    T::max_value()
-}
-
-#[cfg(feature = "lossless")]
+#[cfg(feature = "no-panic")]
 macro_rules! saturate {
     ($value:expr) => {
         match $value {
             Some(value) => value,
-            None => $crate::get_max_saturate(None),
+            None => $crate::Float::MAX,
         }
     };
 }
 
 macro_rules! exec {
     (@ safely $expr:block) => {
-        #[cfg(all(feature = "no-panic", feature = "lossless"))] {
-            #[allow(unused_imports)] use fraction::{CheckedDiv, CheckedMul};
-            break $expr
-        }
+        #[cfg(all(feature = "no-panic", feature = "lossless"))]
+        break $expr
     };
     (@ unsafe $expr:block) => {
         #[cfg(any(not(feature = "no-panic"), not(feature = "lossless")))]
         break $expr
     };
-    (@ bits $expr:block) => {
-        #[cfg(feature = "bits")] break $expr
-    };
-    (@ nobits $expr:block) => {
-        #[cfg(not(feature = "bits"))] break $expr
-    };
     ($($term:tt { $expr:expr }),+) => {
         loop { $( exec!(@ $term { $expr }); )+ }
     };
@@ -101,17 +146,66 @@ macro_rules! bitflags_const_or {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+mod array_string;
 mod bytesize;
+#[cfg(feature = "clap")]
+pub mod clap;
+#[cfg(feature = "std")]
+pub mod clock;
+#[cfg(feature = "lossless")]
+mod decimal;
+mod expr;
+#[cfg(feature = "std")]
+pub mod filter;
+#[cfg(feature = "fs")]
+pub mod fs;
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(feature = "std")]
+mod limiter;
+#[cfg(feature = "std")]
+mod meter;
 mod prefix;
+#[cfg(feature = "alloc")]
+mod progress;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+mod quota;
+mod range;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+mod sample;
+#[cfg(feature = "schemars")]
+mod schema;
+#[cfg(feature = "alloc")]
+pub mod sort;
+#[cfg(any(feature = "sqlx", feature = "diesel"))]
+pub mod sql;
 mod unit;
 
 pub mod prelude {
     pub use super::sizes::all::*;
-    pub use super::{ByteSize, Format, Mode, ReprConfigVariant::*, ReprFormat};
+    pub use super::{BitSize, ByteSize, Format, Mode, ReprConfigVariant::*, ReprFormat};
 }
 
-pub use bytesize::{ByteSize, ByteSizeRepr, Format, Mode, ReprConfigVariant, ReprFormat};
+pub use array_string::ArrayString;
+pub use bytesize::{
+    Backing, BitSize, ByteSize, ByteSizeRepr, ExactRepr, Format, Mode, ReprConfigVariant,
+    ReprFormat,
+};
+#[cfg(feature = "lossless")]
+pub use decimal::Decimal;
+pub use expr::{ExprError, ExprErrorKind};
+#[cfg(feature = "std")]
+pub use limiter::{RateError, RateLimit, Throttled, TokenBucket};
+#[cfg(feature = "std")]
+pub use meter::ThroughputMeter;
 pub use prefix::UnitPrefix;
+#[cfg(feature = "alloc")]
+pub use progress::{BarStyle, ProgressBar, ProgressLine};
+pub use quota::{Quota, QuotaError, QuotaExceeded, QuotaStatus};
+pub use range::{ByteSizeRange, RangeError};
 pub use unit::{sizes, SizeVariant, Unit};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -130,9 +224,9 @@ pub enum ParseError {
     InvalidPrefixCaseFormat,
 }
 
-impl fmt::Display for ParseError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.pad(match self {
+impl ParseError {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
             ParseError::EmptyInput => "empty input",
             ParseError::MissingUnit => "missing unit",
             ParseError::InvalidValue => "invalid value",
@@ -149,8 +243,14 @@ impl fmt::Display for ParseError {
             ParseError::InvalidPrefixCaseFormat => {
                 "invalid case: expected format like 'k', 'K', 'Ki', 'M', 'Mi'"
             }
-        })
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
     }
 }
 
-impl std::error::Error for ParseError {}
+impl core::error::Error for ParseError {}
@@ -0,0 +1,509 @@
+use super::{
+    bytesize::split_list,
+    clock::{Clock, SystemClock},
+    ByteSize, Int, ParseError,
+};
+use std::{
+    fmt,
+    io::{self, Read, Write},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+/// A sustained rate and the burst allowed on top of it
+///
+/// ```
+/// use xbytes::{prelude::*, RateLimit};
+///
+/// let limit = "10 MiB/s, burst 64 MiB".parse::<RateLimit>().unwrap();
+/// assert_eq!(limit.rate(), ByteSize::of(10, MEBI_BYTE));
+/// assert_eq!(limit.burst(), ByteSize::of(64, MEBI_BYTE));
+///
+/// // without an explicit burst, one second's worth is allowed
+/// let limit = "1.5 MB/s".parse::<RateLimit>().unwrap();
+/// assert_eq!(limit.burst(), ByteSize::of(1.5, MEGA_BYTE));
+/// ```
+#[derive(Eq, Copy, Clone, Debug, PartialEq)]
+pub struct RateLimit {
+    rate: ByteSize,
+    burst: ByteSize,
+}
+
+impl RateLimit {
+    pub const fn new(rate: ByteSize, burst: ByteSize) -> Self {
+        Self { rate, burst }
+    }
+
+    /// Amount allowed per second
+    pub const fn rate(&self) -> ByteSize {
+        self.rate
+    }
+
+    /// Amount that may be acquired at once after a period of inactivity
+    pub const fn burst(&self) -> ByteSize {
+        self.burst
+    }
+
+    pub fn bucket(&self) -> TokenBucket {
+        TokenBucket::new(*self)
+    }
+}
+
+impl fmt::Display for RateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/s, burst {}", self.rate, self.burst)
+    }
+}
+
+/// A rate limit that failed to parse
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RateError {
+    /// a rate or burst that isn't a valid size
+    Parse(ParseError),
+    /// a rate missing its `/s`, or a burst missing its `burst` keyword
+    InvalidFormat,
+}
+
+impl From<ParseError> for RateError {
+    fn from(err: ParseError) -> Self {
+        RateError::Parse(err)
+    }
+}
+
+impl fmt::Display for RateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateError::Parse(err) => fmt::Display::fmt(err, f),
+            RateError::InvalidFormat => f.pad(
+                "invalid rate format: expected format like '10 MiB/s' or '10 MiB/s, burst 64 MiB'",
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RateError {}
+
+impl FromStr for RateLimit {
+    type Err = RateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParseError::EmptyInput.into());
+        }
+        let mut parts = split_list(s, |_, after| after.trim_start().starts_with("burst"));
+        let (rate, burst) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(rate), burst, None) => (rate, burst),
+            _ => return Err(RateError::InvalidFormat),
+        };
+        let rate = ["/s", "/sec"]
+            .iter()
+            .find_map(|suffix| rate.trim_end().strip_suffix(suffix))
+            .ok_or(RateError::InvalidFormat)?
+            .trim_end()
+            .parse()?;
+        let burst = match burst {
+            Some(burst) => burst
+                .trim_start()
+                .strip_prefix("burst")
+                .ok_or(RateError::InvalidFormat)?
+                .trim_start()
+                .parse()?,
+            None => rate,
+        };
+        Ok(Self { rate, burst })
+    }
+}
+
+/// A token bucket handing out allowances measured in bytes
+///
+/// The bucket starts full and refills continuously at the configured rate.
+#[derive(Clone, Debug)]
+pub struct TokenBucket<C = SystemClock> {
+    limit: RateLimit,
+    tokens: f64,
+    updated: Instant,
+    clock: C,
+}
+
+impl TokenBucket {
+    pub fn new(limit: RateLimit) -> Self {
+        Self::with_clock(limit, SystemClock)
+    }
+}
+
+impl<C: Clock> TokenBucket<C> {
+    pub fn with_clock(limit: RateLimit, clock: C) -> Self {
+        Self {
+            tokens: limit.burst.whole_bytes() as f64,
+            updated: clock.now(),
+            limit,
+            clock,
+        }
+    }
+
+    pub const fn limit(&self) -> RateLimit {
+        self.limit
+    }
+
+    fn rate(&self) -> f64 {
+        self.limit.rate.whole_bytes() as f64
+    }
+
+    fn burst(&self) -> f64 {
+        self.limit.burst.whole_bytes() as f64
+    }
+
+    fn refill(&mut self) {
+        let now = self.clock.now();
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate()).min(self.burst());
+        self.updated = now;
+    }
+
+    /// Allowance currently available without waiting
+    pub fn available(&mut self) -> ByteSize {
+        self.refill();
+        ByteSize::saturating_from_bytes(self.tokens.max(0.0) as Int)
+    }
+
+    /// Take `amount` from the bucket if it is available right now
+    pub fn try_acquire(&mut self, amount: ByteSize) -> bool {
+        self.refill();
+        let amount = amount.whole_bytes() as f64;
+        if amount <= self.tokens {
+            self.tokens -= amount;
+            return true;
+        }
+        false
+    }
+
+    /// Time to wait before `amount` can be acquired
+    ///
+    /// Returns `None` if `amount` exceeds the burst size or the bucket doesn't
+    /// refill, since waiting would never suffice, or if the wait doesn't fit in a [`Duration`].
+    pub fn time_until_available(&mut self, amount: ByteSize) -> Option<Duration> {
+        self.refill();
+        let amount = amount.whole_bytes() as f64;
+        let missing = amount - self.tokens;
+        if missing <= 0.0 {
+            Some(Duration::ZERO)
+        } else if amount > self.burst() || self.rate() <= 0.0 {
+            None
+        } else {
+            Duration::try_from_secs_f64(missing / self.rate()).ok()
+        }
+    }
+
+    /// Take `amount` from the bucket, sleeping on the clock for as long as needed
+    ///
+    /// Amounts larger than the burst size are allowed and put the bucket in debt,
+    /// delaying subsequent acquisitions. Returns `false` without acquiring anything
+    /// if `amount` isn't available and the bucket doesn't refill, or the wait for
+    /// it doesn't fit in a [`Duration`].
+    pub fn acquire(&mut self, amount: ByteSize) -> bool {
+        let amount = amount.whole_bytes() as f64;
+        let Some(wait) = self.wait_for(amount) else {
+            return false;
+        };
+        if !wait.is_zero() {
+            self.clock.sleep(wait);
+        }
+        self.tokens -= amount;
+        self.refill();
+        true
+    }
+
+    // how long `acquire` would sleep for `amount`, if it can acquire it at all
+    fn wait_for(&mut self, amount: f64) -> Option<Duration> {
+        self.refill();
+        if amount <= self.tokens {
+            Some(Duration::ZERO)
+        } else if self.rate() <= 0.0 {
+            None
+        } else {
+            Duration::try_from_secs_f64((amount - self.tokens) / self.rate()).ok()
+        }
+    }
+}
+
+/// A [`Read`]/[`Write`] adapter that paces transfers through a [`TokenBucket`]
+#[derive(Clone, Debug)]
+pub struct Throttled<T, C = SystemClock> {
+    inner: T,
+    bucket: TokenBucket<C>,
+}
+
+impl<T> Throttled<T> {
+    pub fn new(inner: T, limit: RateLimit) -> Self {
+        Self::with_bucket(inner, TokenBucket::new(limit))
+    }
+}
+
+impl<T, C: Clock> Throttled<T, C> {
+    pub fn with_bucket(inner: T, bucket: TokenBucket<C>) -> Self {
+        Self { inner, bucket }
+    }
+
+    pub fn bucket(&self) -> &TokenBucket<C> {
+        &self.bucket
+    }
+
+    pub fn bucket_mut(&mut self) -> &mut TokenBucket<C> {
+        &mut self.bucket
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    // transfers are split so that no single one exceeds what the bucket can provide
+    fn chunk_len(&mut self, len: usize) -> io::Result<usize> {
+        let len = (len as Int).min(self.bucket.limit.burst.whole_bytes().max(1));
+        if self.bucket.wait_for(len as f64).is_some() {
+            return Ok(len as usize);
+        }
+        // the bucket doesn't refill or the wait doesn't fit in a `Duration`, so only
+        // what's available right now can be transferred
+        let len = len.min(self.bucket.available().whole_bytes());
+        if len == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::WouldBlock,
+                "rate limit doesn't allow any further transfer",
+            ));
+        }
+        Ok(len as usize)
+    }
+
+    fn pay(&mut self, n: usize) -> usize {
+        let paid = self
+            .bucket
+            .acquire(ByteSize::saturating_from_bytes(n as Int));
+        debug_assert!(paid, "transfers are limited to what can be acquired");
+        n
+    }
+}
+
+impl<R: Read, C: Clock> Read for Throttled<R, C> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.chunk_len(buf.len())?;
+        let n = self.inner.read(&mut buf[..len])?;
+        Ok(self.pay(n))
+    }
+}
+
+impl<W: Write, C: Clock> Write for Throttled<W, C> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.chunk_len(buf.len())?;
+        let n = self.inner.write(&buf[..len])?;
+        Ok(self.pay(n))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::sizes::*, *};
+    use crate::clock::ManualClock;
+    use std::rc::Rc;
+
+    const SEC: Duration = Duration::from_secs(1);
+
+    fn bucket(limit: &str) -> (Rc<ManualClock>, TokenBucket<Rc<ManualClock>>) {
+        let clock = Rc::new(ManualClock::new());
+        let bucket = TokenBucket::with_clock(limit.parse().unwrap(), clock.clone());
+        (clock, bucket)
+    }
+
+    #[test]
+    fn parse_rate_limit() {
+        let map = [
+            ("10 MiB/s", Ok((10, MEBI_BYTE, 10, MEBI_BYTE))),
+            ("10MiB/s", Ok((10, MEBI_BYTE, 10, MEBI_BYTE))),
+            ("2 GB/sec", Ok((2, GIGA_BYTE, 2, GIGA_BYTE))),
+            ("10 MiB/s, burst 64 MiB", Ok((10, MEBI_BYTE, 64, MEBI_BYTE))),
+            (
+                "1,024 KiB/s, burst 65,536 KiB",
+                Ok((1, MEBI_BYTE, 64, MEBI_BYTE)),
+            ),
+            ("1,024 KiB/s", Ok((1, MEBI_BYTE, 1, MEBI_BYTE))),
+            (
+                "1 KiB/s, burst 2 KiB, burst 4 KiB",
+                Err(RateError::InvalidFormat),
+            ),
+            (" 1 KiB /s,burst 4KiB ", Ok((1, KIBI_BYTE, 4, KIBI_BYTE))),
+            ("", Err(RateError::Parse(ParseError::EmptyInput))),
+            ("10 MiB", Err(RateError::InvalidFormat)),
+            ("10 MiB/s, 64 MiB", Err(RateError::InvalidFormat)),
+            ("10 XiB/s", Err(RateError::Parse(ParseError::InvalidPrefix))),
+            (
+                "10 MiB/s, burst",
+                Err(RateError::Parse(ParseError::EmptyInput)),
+            ),
+        ];
+
+        for (input, expected) in map.iter() {
+            assert_eq!(
+                expected.map(|(rate, rate_unit, burst, burst_unit)| RateLimit::new(
+                    ByteSize::of(rate, rate_unit),
+                    ByteSize::of(burst, burst_unit)
+                )),
+                input.parse::<RateLimit>(),
+                "while parsing {:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn display_rate_limit() {
+        let limit = "10 MiB/s, burst 64 MiB".parse::<RateLimit>().unwrap();
+        assert_eq!("10 MiB/s, burst 64 MiB", limit.to_string());
+        assert_eq!(Ok(limit), limit.to_string().parse());
+    }
+
+    #[test]
+    fn try_acquire() {
+        let (clock, mut bucket) = bucket("1 KiB/s, burst 4 KiB");
+
+        assert!(bucket.try_acquire(ByteSize::of(3, KIBI_BYTE)));
+        assert!(!bucket.try_acquire(ByteSize::of(2, KIBI_BYTE)));
+        assert_eq!(ByteSize::of(1, KIBI_BYTE), bucket.available());
+
+        clock.advance(SEC);
+        assert!(bucket.try_acquire(ByteSize::of(2, KIBI_BYTE)));
+
+        // refills never exceed the burst size
+        clock.advance(60 * SEC);
+        assert_eq!(ByteSize::of(4, KIBI_BYTE), bucket.available());
+    }
+
+    #[test]
+    fn time_until_available() {
+        let (clock, mut bucket) = bucket("1 KiB/s, burst 4 KiB");
+
+        assert_eq!(
+            Some(Duration::ZERO),
+            bucket.time_until_available(ByteSize::of(4, KIBI_BYTE))
+        );
+        assert_eq!(
+            None,
+            bucket.time_until_available(ByteSize::of(5, KIBI_BYTE))
+        );
+
+        bucket.try_acquire(ByteSize::of(4, KIBI_BYTE));
+        assert_eq!(
+            Some(2 * SEC),
+            bucket.time_until_available(ByteSize::of(2, KIBI_BYTE))
+        );
+
+        clock.advance(SEC / 2);
+        assert_eq!(
+            Some(SEC + SEC / 2),
+            bucket.time_until_available(ByteSize::of(2, KIBI_BYTE))
+        );
+    }
+
+    #[test]
+    fn acquire_sleeps_on_clock() {
+        let (clock, mut bucket) = bucket("1 KiB/s, burst 1 KiB");
+        let start = clock.now();
+
+        assert!(bucket.acquire(ByteSize::of(1, KIBI_BYTE)));
+        assert_eq!(Duration::ZERO, clock.now() - start);
+
+        // beyond the burst, the bucket goes into debt
+        assert!(bucket.acquire(ByteSize::of(3, KIBI_BYTE)));
+        assert_eq!(3 * SEC, clock.now() - start);
+        assert!(!bucket.try_acquire(ByteSize::of(1, BYTE)));
+    }
+
+    #[test]
+    fn waits_too_long_to_represent() {
+        let clock = ManualClock::new();
+        let limit = RateLimit::new(ByteSize::of(1, BYTE), ByteSize::from_bytes(u64::MAX));
+        let mut bucket = TokenBucket::with_clock(limit, &clock);
+        let start = clock.now();
+
+        assert!(bucket.try_acquire(limit.burst()));
+        assert_eq!(None, bucket.time_until_available(limit.burst()));
+        assert!(!bucket.acquire(limit.burst()));
+        assert_eq!(Duration::ZERO, clock.now() - start);
+
+        // nothing was taken from the bucket
+        assert_eq!(
+            Some(2 * SEC),
+            bucket.time_until_available(ByteSize::of(2, BYTE))
+        );
+    }
+
+    #[test]
+    fn throttled_io() {
+        let clock = Rc::new(ManualClock::new());
+        let limit = "1 KiB/s, burst 1 KiB".parse().unwrap();
+        let start = clock.now();
+
+        let data = vec![7; 5 * 1024];
+        let mut reader =
+            Throttled::with_bucket(&data[..], TokenBucket::with_clock(limit, clock.clone()));
+        let mut writer =
+            Throttled::with_bucket(Vec::new(), TokenBucket::with_clock(limit, clock.clone()));
+
+        let mut buf = [0; 4096];
+        assert_eq!(1024, reader.read(&mut buf).unwrap());
+
+        writer.write_all(&data).unwrap();
+        assert_eq!(data, writer.into_inner());
+        assert_eq!(4 * SEC, clock.now() - start);
+
+        // the reader's bucket refilled while the writer was waiting
+        io::copy(&mut reader, &mut io::sink()).unwrap();
+        assert_eq!(7 * SEC, clock.now() - start);
+    }
+
+    #[test]
+    fn throttled_without_refill() {
+        let limit = RateLimit::new(ByteSize::of(0, BYTE), ByteSize::of(10, BYTE));
+        let mut writer = Throttled::new(Vec::new(), limit);
+
+        assert_eq!(10, writer.write(&[0; 16]).unwrap());
+        assert_eq!(
+            io::ErrorKind::WouldBlock,
+            writer.write(&[0; 16]).unwrap_err().kind()
+        );
+        assert_eq!(10, writer.into_inner().len());
+    }
+
+    #[test]
+    fn throttled_waits_too_long_to_represent() {
+        let clock = ManualClock::new();
+        let limit = RateLimit::new(ByteSize::of(1, BYTE), ByteSize::from_bytes(u64::MAX));
+        let mut bucket = TokenBucket::with_clock(limit, &clock);
+        assert!(bucket.try_acquire(limit.burst()));
+        let mut writer = Throttled::with_bucket(Vec::new(), bucket);
+
+        assert_eq!(
+            io::ErrorKind::WouldBlock,
+            writer.chunk_len(usize::MAX).unwrap_err().kind()
+        );
+
+        // only what's available is transferred, while shorter waits are still slept through
+        clock.advance(2 * SEC);
+        assert_eq!(2, writer.chunk_len(usize::MAX).unwrap());
+        let start = clock.now();
+        assert_eq!(16, writer.write(&[0; 16]).unwrap());
+        assert_eq!(14 * SEC, clock.now() - start);
+        assert_eq!(ByteSize::of(0, BYTE), writer.bucket_mut().available());
+    }
+}
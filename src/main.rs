@@ -0,0 +1,358 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::{io, process::ExitCode};
+use xbytes::{
+    filter::{Conversion, Fields, Filter, InvalidPolicy},
+    ByteSize, Format, Mode, ParseError, ReprConfigVariant, Unit,
+};
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum FormatFlag {
+    Default,
+    Initials,
+    Condensed,
+    Long,
+    NoPlural,
+    ForcePlural,
+    NoMultiCaps,
+    LowerCaps,
+    UpperCaps,
+    NoFraction,
+    ForceFraction,
+    ShowThousandsSeparator,
+    NoSpace,
+    Hex,
+}
+
+impl From<FormatFlag> for Format {
+    fn from(flag: FormatFlag) -> Self {
+        match flag {
+            FormatFlag::Default => Format::Default,
+            FormatFlag::Initials => Format::Initials,
+            FormatFlag::Condensed => Format::Condensed,
+            FormatFlag::Long => Format::Long,
+            FormatFlag::NoPlural => Format::NoPlural,
+            FormatFlag::ForcePlural => Format::ForcePlural,
+            FormatFlag::NoMultiCaps => Format::NoMultiCaps,
+            FormatFlag::LowerCaps => Format::LowerCaps,
+            FormatFlag::UpperCaps => Format::UpperCaps,
+            FormatFlag::NoFraction => Format::NoFraction,
+            FormatFlag::ForceFraction => Format::ForceFraction,
+            FormatFlag::ShowThousandsSeparator => Format::ShowThousandsSeparator,
+            FormatFlag::NoSpace => Format::NoSpace,
+            FormatFlag::Hex => Format::Hex,
+        }
+    }
+}
+
+/// Convert between raw byte sizes and human readable sizes
+#[derive(Debug, Parser)]
+#[command(
+    name = "xbytes",
+    version,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// A raw byte count (`123456789`) or a human readable size (`1.5GiB`)
+    #[arg(required_unless_present = "parse", allow_hyphen_values = true)]
+    size: Option<String>,
+
+    /// Parse a human readable size, like `1,024 KiB`
+    #[arg(long, value_name = "SIZE", conflicts_with = "size")]
+    parse: Option<String>,
+
+    /// Represent the size in this unit, instead of picking one
+    #[arg(long, value_name = "UNIT")]
+    to: Option<Unit>,
+
+    /// Print the raw number of bytes
+    #[arg(long, conflicts_with_all = ["bits", "to"])]
+    bytes: bool,
+
+    /// Print the raw number of bits
+    #[arg(long, conflicts_with = "to")]
+    bits: bool,
+
+    #[command(flatten)]
+    repr: ReprArgs,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Convert sizes in selected fields of each line read from stdin, like `numfmt`
+    Filter(FilterArgs),
+}
+
+#[derive(Debug, Args)]
+struct FilterArgs {
+    /// Convert human readable sizes to byte counts, instead of the reverse
+    #[arg(long)]
+    dehumanize: bool,
+
+    /// Fields to convert, like `2`, `1,3`, `2-4` or `3-`
+    #[arg(long, value_name = "FIELDS", default_value = "1", value_parser = |s: &str| s.parse::<Fields>())]
+    field: Fields,
+
+    /// Split fields on this character, instead of runs of whitespace
+    #[arg(long, short, value_name = "CHAR")]
+    delimiter: Option<char>,
+
+    /// Number of leading lines to pass through unchanged
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    header: usize,
+
+    /// Pad converted fields to this width, left-aligned if negative
+    #[arg(long, value_name = "N", allow_hyphen_values = true)]
+    padding: Option<isize>,
+
+    /// What to do with fields that can't be converted: fail, warn or ignore
+    #[arg(long, value_name = "MODE", default_value = "fail", value_parser = |s: &str| s.parse::<InvalidPolicy>())]
+    invalid: InvalidPolicy,
+
+    #[command(flatten)]
+    repr: ReprArgs,
+}
+
+#[derive(Debug, Args)]
+struct ReprArgs {
+    /// How to pick the unit of the representation
+    #[arg(long)]
+    mode: Option<Mode>,
+
+    /// How to format the representation
+    #[arg(long, value_enum, value_delimiter = ',')]
+    format: Vec<FormatFlag>,
+
+    /// Number of digits after the decimal point
+    #[arg(long, value_name = "DIGITS")]
+    precision: Option<usize>,
+
+    /// Number of spaces between the value and the unit
+    #[arg(long, value_name = "N")]
+    spaces: Option<usize>,
+
+    /// Separator between groups of thousands, implies `--format show-thousands-separator`
+    #[arg(long, value_name = "SEP")]
+    separator: Option<char>,
+}
+
+impl ReprArgs {
+    fn mode(&self) -> Mode {
+        self.mode.unwrap_or(Mode::Default)
+    }
+
+    fn format(self) -> (Format, Vec<ReprConfigVariant>) {
+        let mut format = self
+            .format
+            .into_iter()
+            .map(Format::from)
+            .fold(Format::Default, |a, b| a | b);
+        let mut variants = vec![];
+        if let Some(separator) = self.separator {
+            format |= Format::ShowThousandsSeparator;
+            variants.push(ReprConfigVariant::ThousandsSeparator(separator));
+        }
+        if let Some(precision) = self.precision {
+            variants.push(ReprConfigVariant::Precision(precision));
+        }
+        if let Some(spaces) = self.spaces {
+            variants.push(ReprConfigVariant::Spaces(spaces));
+        }
+        (format, variants)
+    }
+}
+
+fn parse_size(input: &str) -> Result<ByteSize, ParseError> {
+    let input = input.trim();
+    // a bare number is a count of bytes
+    if input
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, ',' | '.'))
+    {
+        return format!("{} B", input).parse();
+    }
+    input.parse()
+}
+
+fn run(cli: Cli) -> Result<String, ParseError> {
+    let size = parse_size(cli.size.as_deref().or(cli.parse.as_deref()).unwrap_or(""))?;
+
+    if cli.bytes {
+        return Ok(size.bytes().to_string());
+    }
+    if cli.bits {
+        return Ok(size.bits()?.to_string());
+    }
+
+    let mode = cli.repr.mode();
+    let (format, variants) = cli.repr.format();
+    let repr = match cli.to {
+        Some(unit) => size.repr_as(unit),
+        None => size.repr(mode),
+    };
+    Ok(variants
+        .iter()
+        .fold(repr, |repr, variant| repr.with(variant))
+        .with(format)
+        .to_string())
+}
+
+fn filter(args: FilterArgs) -> Filter {
+    let mut filter = Filter::new()
+        .with_fields(args.field)
+        .with_header(args.header)
+        .with_invalid(args.invalid)
+        .with_mode(args.repr.mode());
+    if args.dehumanize {
+        filter = filter.with_conversion(Conversion::Dehumanize);
+    }
+    if let Some(delimiter) = args.delimiter {
+        filter = filter.with_delimiter(delimiter);
+    }
+    if let Some(padding) = args.padding {
+        filter = filter.with_padding(padding);
+    }
+    let (format, variants) = args.repr.format();
+    variants
+        .iter()
+        .fold(filter, |filter, variant| filter.with(variant))
+        .with(format)
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Some(Command::Filter(args)) = cli.command {
+        return match filter(args).run(io::stdin().lock(), io::stdout().lock(), io::stderr()) {
+            Ok(_) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("xbytes: {}", err);
+                ExitCode::FAILURE
+            }
+        };
+    }
+    match run(cli) {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("xbytes: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn xbytes(args: &[&str]) -> Result<String, ParseError> {
+        run(Cli::try_parse_from(std::iter::once("xbytes").chain(args.iter().copied())).unwrap())
+    }
+
+    #[test]
+    fn convert() {
+        assert_eq!(Ok("1.50 GiB".to_string()), xbytes(&["1.5GiB"]));
+        assert_eq!(
+            Ok("1610.61 MB".to_string()),
+            xbytes(&["1.5GiB", "--to", "MB"])
+        );
+        assert_eq!(
+            Ok("123.4 MB".to_string()),
+            xbytes(&["123456789", "--mode", "decimal", "--precision", "1"])
+        );
+        assert_eq!(
+            Ok("987.6 Mb".to_string()),
+            xbytes(&["123456789", "--mode", "decimal,bits", "--precision", "1"])
+        );
+        assert_eq!(Ok("1 MiB".to_string()), xbytes(&["1,048,576"]));
+    }
+
+    #[test]
+    fn raw_counts() {
+        assert_eq!(
+            Ok("1048576".to_string()),
+            xbytes(&["--parse", "1,024 KiB", "--bytes"])
+        );
+        assert_eq!(Ok("8192".to_string()), xbytes(&["1 KiB", "--bits"]));
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(
+            Ok("1 KiloByte".to_string()),
+            xbytes(&["1000", "--mode", "decimal", "--format", "long"])
+        );
+        assert_eq!(
+            Ok("8.00kb".to_string()),
+            xbytes(&[
+                "1000",
+                "--mode",
+                "decimal,bits",
+                "--format",
+                "force-fraction,lower-caps,no-space",
+                "--precision",
+                "2"
+            ])
+        );
+        assert_eq!(
+            Ok("2_048  MiB".to_string()),
+            xbytes(&["2 GiB", "--to", "MiB", "--separator", "_", "--spaces", "2"])
+        );
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(Err(ParseError::InvalidPrefix), xbytes(&["1.5 XiB"]));
+        assert_eq!(Err(ParseError::InvalidThousandsFormat), xbytes(&["1,00"]));
+        assert!(Cli::try_parse_from(["xbytes", "1 MiB", "--to", "XB"]).is_err());
+        assert!(Cli::try_parse_from(["xbytes", "1 MiB", "--mode", "hex"]).is_err());
+        assert!(Cli::try_parse_from(["xbytes"]).is_err());
+    }
+
+    fn xbytes_filter(args: &[&str], input: &str) -> String {
+        let cli = Cli::try_parse_from(["xbytes", "filter"].into_iter().chain(args.iter().copied()))
+            .unwrap();
+        let Some(Command::Filter(args)) = cli.command else {
+            panic!("expected the filter subcommand");
+        };
+        let mut output = vec![];
+        filter(args)
+            .run(input.as_bytes(), &mut output, io::sink())
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn filter_lines() {
+        assert_eq!(
+            "size name\n1 KiB a\n  1.50 MiB b\n",
+            xbytes_filter(&["--header", "1"], "size name\n1024 a\n  1572864 b\n")
+        );
+        assert_eq!(
+            "a:1000000   :c\n",
+            xbytes_filter(
+                &["--dehumanize", "-d", ":", "--field", "2", "--padding=-10"],
+                "a:1MB:c\n"
+            )
+        );
+        assert_eq!(
+            "x 1.5KB\n",
+            xbytes_filter(
+                &[
+                    "--field=2",
+                    "--mode=decimal",
+                    "--format=no-space",
+                    "--precision=1",
+                    "--invalid=ignore"
+                ],
+                "x 1500\n"
+            )
+        );
+        assert!(Cli::try_parse_from(["xbytes", "filter", "--invalid", "abort"]).is_err());
+        assert!(Cli::try_parse_from(["xbytes", "1 KiB", "filter"]).is_err());
+    }
+}
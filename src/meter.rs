@@ -0,0 +1,294 @@
+use super::{
+    clock::{Clock, SystemClock},
+    ByteSize, Int,
+};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Smoothed transfer rate measurements over a stream of samples
+///
+/// Rates are reported as the amount of data transferred per second.
+///
+/// ```
+/// use std::{rc::Rc, time::Duration};
+/// use xbytes::{clock::ManualClock, prelude::*, ThroughputMeter};
+///
+/// let clock = Rc::new(ManualClock::new());
+/// let mut meter = ThroughputMeter::with_clock(clock.clone());
+///
+/// for _ in 0..4 {
+///     clock.advance(Duration::from_secs(1));
+///     meter.add(ByteSize::of(2, MEBI_BYTE));
+/// }
+///
+/// assert_eq!(meter.rate().to_string(), "2 MiB");
+/// assert_eq!(meter.eta(ByteSize::of(16, MEBI_BYTE)), Some(Duration::from_secs(4)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ThroughputMeter<C = SystemClock> {
+    clock: C,
+    window: Duration,
+    half_life: Duration,
+    samples: VecDeque<(Instant, Int)>,
+    smoothed: (Instant, Int, Option<f64>),
+    peak: f64,
+}
+
+impl ThroughputMeter {
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock)
+    }
+}
+
+impl Default for ThroughputMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Clock> ThroughputMeter<C> {
+    pub fn with_clock(clock: C) -> Self {
+        let now = clock.now();
+        Self {
+            clock,
+            window: Duration::from_secs(10),
+            half_life: Duration::from_secs(2),
+            samples: VecDeque::from(vec![(now, 0)]),
+            smoothed: (now, 0, None),
+            peak: 0.0,
+        }
+    }
+
+    /// Span of time covered by the sliding-window rate (default: 10s)
+    pub fn with_window(self, window: Duration) -> Self {
+        Self { window, ..self }
+    }
+
+    /// Time it takes for a sample's weight in the exponentially
+    /// weighted rate to decay by half (default: 2s)
+    pub fn with_half_life(self, half_life: Duration) -> Self {
+        Self { half_life, ..self }
+    }
+
+    /// Record `amount` as transferred right now
+    pub fn add(&mut self, amount: ByteSize) {
+        let at = self.clock.now();
+        let total = self.total_bytes().saturating_add(amount.whole_bytes());
+        self.record(at, total);
+    }
+
+    /// Record the cumulative `total` transferred as observed at `at`
+    ///
+    /// Samples older than the most recent one are treated as taken at the same time.
+    pub fn sample(&mut self, at: Instant, total: ByteSize) {
+        self.record(at, total.whole_bytes());
+    }
+
+    fn record(&mut self, at: Instant, total: Int) {
+        let &(last_at, _) = self
+            .samples
+            .back()
+            .expect("meter always holds a baseline sample");
+        let at = at.max(last_at);
+
+        let (smoothed_at, smoothed_total, smoothed_rate) = self.smoothed;
+        let elapsed = at.duration_since(smoothed_at).as_secs_f64();
+        if elapsed > 0.0 {
+            let rate = total.saturating_sub(smoothed_total) as f64 / elapsed;
+            let rate = match smoothed_rate {
+                // seed the average with the first measurement instead of zero
+                None => rate,
+                Some(smoothed) => {
+                    smoothed + (1.0 - decay(elapsed, self.half_life)) * (rate - smoothed)
+                }
+            };
+            self.smoothed = (at, total, Some(rate));
+        }
+
+        self.samples.push_back((at, total));
+        while self.samples.len() > 2 && at.duration_since(self.samples[1].0) >= self.window {
+            self.samples.pop_front();
+        }
+        self.peak = self.peak.max(self.window_rate(at));
+    }
+
+    fn total_bytes(&self) -> Int {
+        self.samples.back().map_or(0, |&(_, total)| total)
+    }
+
+    fn window_rate(&self, now: Instant) -> f64 {
+        let (&(first_at, first), &(_, last)) = match (self.samples.front(), self.samples.back()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return 0.0,
+        };
+        match now.duration_since(first_at).as_secs_f64() {
+            elapsed if elapsed > 0.0 => last.saturating_sub(first) as f64 / elapsed,
+            _ => 0.0,
+        }
+    }
+
+    /// Total amount recorded so far
+    pub fn total(&self) -> ByteSize {
+        ByteSize::saturating_from_bytes(self.total_bytes())
+    }
+
+    /// Average rate over the sliding window ending now
+    pub fn rate(&self) -> ByteSize {
+        per_second(self.window_rate(self.clock.now()))
+    }
+
+    /// Exponentially weighted moving average of the rate
+    ///
+    /// Time passed since the last sample counts as a period of no transfer.
+    pub fn ewma_rate(&self) -> ByteSize {
+        per_second(self.ewma())
+    }
+
+    fn ewma(&self) -> f64 {
+        let (smoothed_at, _, rate) = self.smoothed;
+        let idle = self.clock.now().saturating_duration_since(smoothed_at);
+        rate.unwrap_or(0.0) * decay(idle.as_secs_f64(), self.half_life)
+    }
+
+    /// Highest sliding-window rate observed at any sample
+    pub fn peak_rate(&self) -> ByteSize {
+        per_second(self.peak)
+    }
+
+    /// Estimated time until `target` is reached at the current smoothed rate
+    ///
+    /// Returns `None` if nothing is being transferred, or the rate
+    /// has decayed so far the wait no longer fits in a [`Duration`].
+    pub fn eta(&self, target: ByteSize) -> Option<Duration> {
+        match target.whole_bytes().saturating_sub(self.total_bytes()) {
+            0 => Some(Duration::ZERO),
+            remaining => match self.ewma() {
+                rate if rate > 0.0 => Duration::try_from_secs_f64(remaining as f64 / rate).ok(),
+                _ => None,
+            },
+        }
+    }
+}
+
+// fraction of the weight left after `elapsed` seconds
+fn decay(elapsed: f64, half_life: Duration) -> f64 {
+    match half_life.as_secs_f64() {
+        half_life if half_life > 0.0 => 0.5f64.powf(elapsed / half_life),
+        _ => 0.0,
+    }
+}
+
+fn per_second(bytes: f64) -> ByteSize {
+    ByteSize::saturating_from_bytes(bytes.round() as Int)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::sizes::*, *};
+    use crate::clock::ManualClock;
+    use std::rc::Rc;
+
+    const SEC: Duration = Duration::from_secs(1);
+
+    fn meter() -> (Rc<ManualClock>, ThroughputMeter<Rc<ManualClock>>) {
+        let clock = Rc::new(ManualClock::new());
+        (clock.clone(), ThroughputMeter::with_clock(clock))
+    }
+
+    #[test]
+    fn steady_rate() {
+        let (clock, mut meter) = meter();
+
+        for _ in 0..20 {
+            clock.advance(SEC);
+            meter.add(ByteSize::of(1, MEBI_BYTE));
+        }
+
+        assert_eq!(ByteSize::of(20, MEBI_BYTE), meter.total());
+        assert_eq!(ByteSize::of(1, MEBI_BYTE), meter.rate());
+        assert_eq!(ByteSize::of(1, MEBI_BYTE), meter.ewma_rate());
+        assert_eq!(ByteSize::of(1, MEBI_BYTE), meter.peak_rate());
+    }
+
+    #[test]
+    fn sliding_window() {
+        let (clock, meter) = meter();
+        let mut meter = meter.with_window(4 * SEC);
+
+        for amount in [8, 8, 8, 8, 2, 2, 2, 2] {
+            clock.advance(SEC);
+            meter.add(ByteSize::of(amount, KIBI_BYTE));
+        }
+
+        assert_eq!(ByteSize::of(2, KIBI_BYTE), meter.rate());
+        assert_eq!(ByteSize::of(8, KIBI_BYTE), meter.peak_rate());
+
+        // idle time drags the window rate down
+        clock.advance(4 * SEC);
+        assert_eq!(ByteSize::of(1, KIBI_BYTE), meter.rate());
+    }
+
+    #[test]
+    fn ewma_smooths_jitter() {
+        let (clock, meter) = meter();
+        let mut meter = meter.with_half_life(SEC);
+
+        clock.advance(SEC);
+        meter.add(ByteSize::of(4, KIBI_BYTE));
+        assert_eq!(ByteSize::of(4, KIBI_BYTE), meter.ewma_rate());
+
+        // a sample one half-life later carries half of the weight
+        clock.advance(SEC);
+        meter.add(ByteSize::of(0, BYTE));
+        assert_eq!(ByteSize::of(2, KIBI_BYTE), meter.ewma_rate());
+
+        clock.advance(SEC);
+        meter.add(ByteSize::of(6, KIBI_BYTE));
+        assert_eq!(ByteSize::of(4, KIBI_BYTE), meter.ewma_rate());
+
+        // decays while idle
+        clock.advance(SEC);
+        assert_eq!(ByteSize::of(2, KIBI_BYTE), meter.ewma_rate());
+    }
+
+    #[test]
+    fn samples_and_eta() {
+        let (clock, mut meter) = meter();
+        let start = clock.now();
+
+        meter.sample(start + 2 * SEC, ByteSize::of(2, GIBI_BYTE));
+        meter.sample(start + 4 * SEC, ByteSize::of(4, GIBI_BYTE));
+        meter.sample(start + 6 * SEC, ByteSize::of(6, GIBI_BYTE));
+        clock.advance(6 * SEC);
+
+        assert_eq!(ByteSize::of(6, GIBI_BYTE), meter.total());
+        assert_eq!(ByteSize::of(1, GIBI_BYTE), meter.rate());
+        assert_eq!(Some(Duration::ZERO), meter.eta(ByteSize::of(5, GIBI_BYTE)));
+
+        assert_eq!(Some(4 * SEC), meter.eta(ByteSize::of(10, GIBI_BYTE)));
+    }
+
+    #[test]
+    fn no_transfer() {
+        let (clock, meter) = meter();
+        clock.advance(SEC);
+
+        assert_eq!(ByteSize::of(0, BYTE), meter.rate());
+        assert_eq!(ByteSize::of(0, BYTE), meter.ewma_rate());
+        assert_eq!(None, meter.eta(ByteSize::of(1, BYTE)));
+    }
+
+    #[test]
+    fn eta_after_long_idle() {
+        let (clock, mut meter) = meter();
+
+        clock.advance(SEC);
+        meter.add(ByteSize::of(1, MEBI_BYTE));
+        clock.advance(300 * SEC);
+
+        assert!(meter.ewma_rate() < ByteSize::of(1, BYTE));
+        assert_eq!(None, meter.eta(ByteSize::of(1, GIBI_BYTE)));
+    }
+}
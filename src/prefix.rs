@@ -1,5 +1,5 @@
 use super::{Int, ParseError};
-use std::{fmt, str::FromStr};
+use core::{fmt, str::FromStr};
 
 #[rustfmt::skip]
 #[derive(Eq, Ord, Copy, Clone, Debug, PartialEq, PartialOrd)]
@@ -10,55 +10,36 @@ pub enum UnitPrefix {
     Tera, Tebi,
     Peta, Pebi,
     Exa , Exbi,
-    #[cfg(feature = "u128")] Zetta,
-    #[cfg(feature = "u128")] Zebi ,
-    #[cfg(feature = "u128")] Yotta,
-    #[cfg(feature = "u128")] Yobi ,
+    Zetta, Zebi,
+    Yotta, Yobi,
 }
 
 use UnitPrefix::*;
 
 impl UnitPrefix {
     #[rustfmt::skip]
-    pub const DECIMAL: [UnitPrefix; {
-        #[cfg(feature = "u128")] { 8 }
-        #[cfg(not(feature = "u128"))] { 6 }
-    }] = [
+    pub const DECIMAL: [UnitPrefix; 8] = [
         Kilo, Mega, Giga, Tera, Peta, Exa,
-        #[cfg(feature = "u128")] Zetta,
-        #[cfg(feature = "u128")] Yotta,
+        Zetta, Yotta,
     ];
 
     #[rustfmt::skip]
-    pub const BINARY: [UnitPrefix; {
-        #[cfg(feature = "u128")] { 8 }
-        #[cfg(not(feature = "u128"))] { 6 }
-    }] = [
+    pub const BINARY: [UnitPrefix; 8] = [
         Kibi, Mebi, Gibi, Tebi, Pebi, Exbi,
-        #[cfg(feature = "u128")] Zebi,
-        #[cfg(feature = "u128")] Yobi,
+        Zebi, Yobi,
     ];
 
     #[rustfmt::skip]
-    pub const ALL: [UnitPrefix; {
-        #[cfg(feature = "u128")] { 16 }
-        #[cfg(not(feature = "u128"))] { 12 }
-    }] = [
+    pub const ALL: [UnitPrefix; 16] = [
         Kilo, Kibi, Mega, Mebi, Giga, Gibi,
         Tera, Tebi, Peta, Pebi, Exa, Exbi,
-        #[cfg(feature = "u128")] Zetta,
-        #[cfg(feature = "u128")] Zebi,
-        #[cfg(feature = "u128")] Yotta,
-        #[cfg(feature = "u128")] Yobi,
+        Zetta, Zebi, Yotta, Yobi,
     ];
 
     pub const MIN: UnitPrefix = Kilo;
 
     #[rustfmt::skip]
-    pub const MAX: UnitPrefix = {
-        #[cfg(feature = "u128")]      { Yobi }
-        #[cfg(not(feature = "u128"))] { Exbi }
-    };
+    pub const MAX: UnitPrefix = Yobi;
 
     pub const fn is_decimal(&self) -> bool {
         ((*self as u8) & 1) == 0
@@ -96,10 +77,10 @@ impl UnitPrefix {
             Tebi => 1 << 40,   Tera => 1000000000000,
             Pebi => 1 << 50,   Peta => 1000000000000000,
             Exbi => 1 << 60,   Exa  => 1000000000000000000,
-            #[cfg(feature = "u128")] Zebi  => 1 << 70,
-            #[cfg(feature = "u128")] Yobi  => 1 << 80,
-            #[cfg(feature = "u128")] Zetta => 1000000000000000000000,
-            #[cfg(feature = "u128")] Yotta => 1000000000000000000000000,
+            Zebi  => 1 << 70,
+            Yobi  => 1 << 80,
+            Zetta => 1000000000000000000000,
+            Yotta => 1000000000000000000000000,
         }
     }
 
@@ -112,10 +93,10 @@ impl UnitPrefix {
             Tera => "T",   Tebi => "Ti",
             Peta => "P",   Pebi => "Pi",
             Exa  => "E",   Exbi => "Ei",
-            #[cfg(feature = "u128")] Zetta => "Z" ,
-            #[cfg(feature = "u128")] Yotta => "Y" ,
-            #[cfg(feature = "u128")] Zebi  => "Zi",
-            #[cfg(feature = "u128")] Yobi  => "Yi",
+            Zetta => "Z" ,
+            Yotta => "Y" ,
+            Zebi  => "Zi",
+            Yobi  => "Yi",
         }
     }
 
@@ -128,10 +109,10 @@ impl UnitPrefix {
             Tera => "Tera",   Tebi => "Tebi",
             Peta => "Peta",   Pebi => "Pebi",
             Exa  => "Exa" ,   Exbi => "Exbi",
-            #[cfg(feature = "u128")] Zetta => "Zetta",
-            #[cfg(feature = "u128")] Yotta => "Yotta",
-            #[cfg(feature = "u128")] Zebi  => "Zebi" ,
-            #[cfg(feature = "u128")] Yobi  => "Yobi" ,
+            Zetta => "Zetta",
+            Yotta => "Yotta",
+            Zebi  => "Zebi" ,
+            Yobi  => "Yobi" ,
         }
     }
 
@@ -144,8 +125,8 @@ impl UnitPrefix {
             Tera | Tebi => "T",
             Peta | Pebi => "P",
             Exa  | Exbi => "E",
-            #[cfg(feature = "u128")] Zetta | Zebi => "Z",
-            #[cfg(feature = "u128")] Yotta | Yobi => "Y",
+            Zetta | Zebi => "Z",
+            Yotta | Yobi => "Y",
         }
     }
 }
@@ -167,18 +148,15 @@ impl FromStr for UnitPrefix {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        #[cfg(feature = "case-insensitive")]
+        if let Some(prefix) = UnitPrefix::ALL
+            .iter()
+            .find(|p| p.symbol().eq_ignore_ascii_case(s))
+        {
+            return Ok(*prefix);
+        }
         #[rustfmt::skip]
-        let unit = match &{
-            #[cfg(not(feature = "case-insensitive"))] { s }
-            #[cfg(feature = "case-insensitive")] {
-                if !s.is_empty() {
-                    let (first, rest) = s.split_at(1);
-                    format!("{}{}", first.to_uppercase(), rest.to_lowercase())
-                } else {
-                    s.to_string()
-                }
-            }
-        }[..] {
+        let unit = match s {
             "" => return Err(ParseError::EmptyInput),
             // https://web.archive.org/web/20150324153922/https://pacoup.com/2009/05/26/kb-kb-kib-whats-up-with-that/
             "k" | "K"  => Kilo,   "Ki"  => Kibi,
@@ -187,28 +165,17 @@ impl FromStr for UnitPrefix {
             "T"        => Tera,   "Ti"  => Tebi,
             "P"        => Peta,   "Pi"  => Pebi,
             "E"        => Exa ,   "Ei"  => Exbi,
-            #[cfg(feature = "u128")] "Z"   => Zetta,
-            #[cfg(feature = "u128")] "Y"   => Yotta,
-            #[cfg(feature = "u128")] "Zi"  => Zebi ,
-            #[cfg(feature = "u128")] "Yi"  => Yobi ,
+            "Z"        => Zetta,  "Zi"  => Zebi ,
+            "Y"        => Yotta,  "Yi"  => Yobi ,
             #[cfg(not(feature = "case-insensitive"))]
-            s if (
-                matches!(s,
-                    "m" | "g" | "t" | "p" | "e" | "ki" | "mi" | "gi" | "ti" | "pi" | "ei"
-                ) || (cfg!(feature = "u128") && matches!(s, "z" | "y" | "zi" | "yi"))
+            s if matches!(s,
+                "m" | "g" | "t" | "p" | "e" | "z" | "y" |
+                "ki" | "mi" | "gi" | "ti" | "pi" | "ei" | "zi" | "yi"
             ) => return Err(ParseError::InvalidPrefixCaseFormat),
-            s => match s.to_lowercase().as_str() {
-                "kilo" => Kilo,   "kibi" => Kibi,
-                "mega" => Mega,   "mebi" => Mebi,
-                "giga" => Giga,   "gibi" => Gibi,
-                "tera" => Tera,   "tebi" => Tebi,
-                "peta" => Peta,   "pebi" => Pebi,
-                "exa"  => Exa ,   "exbi" => Exbi,
-                #[cfg(feature = "u128")] "zetta" => Zetta,
-                #[cfg(feature = "u128")] "yotta" => Yotta,
-                #[cfg(feature = "u128")] "zebi"  => Zebi ,
-                #[cfg(feature = "u128")] "yobi"  => Yobi ,
-                _ => return Err(ParseError::InvalidPrefix),
+            // long names are case-insensitive: "kilo", "Kilo", "KILO"
+            s => match UnitPrefix::ALL.iter().find(|p| p.symbol_long().eq_ignore_ascii_case(s)) {
+                Some(prefix) => *prefix,
+                None => return Err(ParseError::InvalidPrefix),
             }
         };
         Ok(unit)
@@ -224,8 +191,8 @@ mod tests {
         #[rustfmt::skip]
         let lhs = [
             Kilo, Mega, Giga, Tera, Peta, Exa,
-            #[cfg(feature = "u128")] Zetta,
-            #[cfg(feature = "u128")] Yotta
+            Zetta,
+            Yotta
         ];
 
         for (index, unit) in lhs.iter().enumerate() {
@@ -239,8 +206,8 @@ mod tests {
         #[rustfmt::skip]
         let lhs = [
             Kibi, Mebi, Gibi, Tebi, Pebi, Exbi,
-            #[cfg(feature = "u128")] Zebi,
-            #[cfg(feature = "u128")] Yobi
+            Zebi,
+            Yobi
         ];
 
         for (index, unit) in lhs.iter().enumerate() {
@@ -251,6 +218,7 @@ mod tests {
 
     #[test]
     #[rustfmt::skip]
+    #[allow(clippy::nonminimal_bool)]
     fn cmp() {
         assert!(Kilo < Kibi && Kibi > Kilo);
         assert!(Kibi < Mega && Mega > Kibi);
@@ -264,10 +232,10 @@ mod tests {
         assert!(Pebi < Exa  && Exa  > Pebi);
         assert!(Exa  < Exbi && Exbi > Exa );
 
-        #[cfg(feature = "u128")] assert!(Exbi  < Zetta && Zetta > Exbi );
-        #[cfg(feature = "u128")] assert!(Zetta < Zebi  && Zebi  > Zetta);
-        #[cfg(feature = "u128")] assert!(Zebi  < Yotta && Yotta > Zebi );
-        #[cfg(feature = "u128")] assert!(Yotta < Yobi  && Yobi  > Yotta);
+        assert!(Exbi  < Zetta && Zetta > Exbi );
+        assert!(Zetta < Zebi  && Zebi  > Zetta);
+        assert!(Zebi  < Yotta && Yotta > Zebi );
+        assert!(Yotta < Yobi  && Yobi  > Yotta);
     }
 
     #[test]
@@ -314,10 +282,10 @@ mod tests {
             (Tera,   3), (Tebi,   3),
             (Peta,   4), (Pebi,   4),
             (Exa ,   5), (Exbi,   5),
-            #[cfg(feature = "u128")] (Zetta,   6),
-            #[cfg(feature = "u128")] (Yotta,   7),
-            #[cfg(feature = "u128")] (Zebi ,   6),
-            #[cfg(feature = "u128")] (Yobi ,   7),
+            (Zetta,   6),
+            (Yotta,   7),
+            (Zebi ,   6),
+            (Yobi ,   7),
         ];
 
         for (unit, index) in map.iter() {
@@ -341,10 +309,10 @@ mod tests {
             (Tera,   Tera), (Tebi,   Tera),
             (Peta,   Peta), (Pebi,   Peta),
             (Exa ,   Exa ), (Exbi,   Exa ),
-            #[cfg(feature = "u128")] (Zetta,   Zetta),
-            #[cfg(feature = "u128")] (Yotta,   Yotta),
-            #[cfg(feature = "u128")] (Zebi ,   Zetta),
-            #[cfg(feature = "u128")] (Yobi ,   Yotta),
+            (Zetta,   Zetta),
+            (Yotta,   Yotta),
+            (Zebi ,   Zetta),
+            (Yobi ,   Yotta),
         ];
 
         for (unit, expected) in map.iter() {
@@ -368,10 +336,10 @@ mod tests {
             (Tera,   Tebi), (Tebi,   Tebi),
             (Peta,   Pebi), (Pebi,   Pebi),
             (Exa ,   Exbi), (Exbi,   Exbi),
-            #[cfg(feature = "u128")] (Zetta,   Zebi),
-            #[cfg(feature = "u128")] (Yotta,   Yobi),
-            #[cfg(feature = "u128")] (Zebi ,   Zebi),
-            #[cfg(feature = "u128")] (Yobi ,   Yobi),
+            (Zetta,   Zebi),
+            (Yotta,   Yobi),
+            (Zebi ,   Zebi),
+            (Yobi ,   Yobi),
         ];
 
         for (unit, expected) in map.iter() {
@@ -395,10 +363,10 @@ mod tests {
             (Tera,   "T"), (Tebi,   "Ti"),
             (Peta,   "P"), (Pebi,   "Pi"),
             (Exa ,   "E"), (Exbi,   "Ei"),
-            #[cfg(feature = "u128")] (Zetta,   "Z" ),
-            #[cfg(feature = "u128")] (Yotta,   "Y" ),
-            #[cfg(feature = "u128")] (Zebi ,   "Zi"),
-            #[cfg(feature = "u128")] (Yobi ,   "Yi"),
+            (Zetta,   "Z" ),
+            (Yotta,   "Y" ),
+            (Zebi ,   "Zi"),
+            (Yobi ,   "Yi"),
         ];
 
         for (unit, repr) in map.iter() {
@@ -429,10 +397,10 @@ mod tests {
             (Tera,   "Tera"),  (Tebi,   "Tebi"),
             (Peta,   "Peta"),  (Pebi,   "Pebi"),
             (Exa ,   "Exa" ),  (Exbi,   "Exbi"),
-            #[cfg(feature = "u128")] (Zetta,   "Zetta"),
-            #[cfg(feature = "u128")] (Yotta,   "Yotta"),
-            #[cfg(feature = "u128")] (Zebi ,   "Zebi" ),
-            #[cfg(feature = "u128")] (Yobi ,   "Yobi" ),
+            (Zetta,   "Zetta"),
+            (Yotta,   "Yotta"),
+            (Zebi ,   "Zebi" ),
+            (Yobi ,   "Yobi" ),
         ];
 
         for (unit, repr) in map.iter() {
@@ -463,10 +431,10 @@ mod tests {
             (Tera,   "T"),  (Tebi,   "T"),
             (Peta,   "P"),  (Pebi,   "P"),
             (Exa ,   "E"),  (Exbi,   "E"),
-            #[cfg(feature = "u128")] (Zetta,   "Z"),
-            #[cfg(feature = "u128")] (Yotta,   "Y"),
-            #[cfg(feature = "u128")] (Zebi ,   "Z"),
-            #[cfg(feature = "u128")] (Yobi ,   "Y"),
+            (Zetta,   "Z"),
+            (Yotta,   "Y"),
+            (Zebi ,   "Z"),
+            (Yobi ,   "Y"),
         ];
 
         for (unit, repr) in map.iter() {
@@ -498,10 +466,10 @@ mod tests {
             ("T"  , Ok(Tera)),  ("Ti"  , Ok(Tebi)),
             ("P"  , Ok(Peta)),  ("Pi"  , Ok(Pebi)),
             ("E"  , Ok(Exa )),  ("Ei"  , Ok(Exbi)),
-            #[cfg(feature = "u128")] ("Z" , Ok(Zetta)),
-            #[cfg(feature = "u128")] ("Y" , Ok(Yotta)),
-            #[cfg(feature = "u128")] ("Zi", Ok(Zebi )),
-            #[cfg(feature = "u128")] ("Yi", Ok(Yobi )),
+            ("Z" , Ok(Zetta)),
+            ("Y" , Ok(Yotta)),
+            ("Zi", Ok(Zebi )),
+            ("Yi", Ok(Yobi )),
             #[cfg(feature = "case-insensitive")] ("k" , Ok(Kilo)),
             #[cfg(feature = "case-insensitive")] ("ki", Ok(Kibi)),
             #[cfg(feature = "case-insensitive")] ("m" , Ok(Mega)),
@@ -514,10 +482,10 @@ mod tests {
             #[cfg(feature = "case-insensitive")] ("pi", Ok(Pebi)),
             #[cfg(feature = "case-insensitive")] ("e" , Ok(Exa )),
             #[cfg(feature = "case-insensitive")] ("ei", Ok(Exbi)),
-            #[cfg(feature = "case-insensitive")] #[cfg(feature = "u128")] ("z" , Ok(Zetta)),
-            #[cfg(feature = "case-insensitive")] #[cfg(feature = "u128")] ("y" , Ok(Yotta)),
-            #[cfg(feature = "case-insensitive")] #[cfg(feature = "u128")] ("zi", Ok(Zebi )),
-            #[cfg(feature = "case-insensitive")] #[cfg(feature = "u128")] ("yi", Ok(Yobi )),
+            #[cfg(feature = "case-insensitive")] ("z" , Ok(Zetta)),
+            #[cfg(feature = "case-insensitive")] ("y" , Ok(Yotta)),
+            #[cfg(feature = "case-insensitive")] ("zi", Ok(Zebi )),
+            #[cfg(feature = "case-insensitive")] ("yi", Ok(Yobi )),
             #[cfg(feature = "case-insensitive")] ("kI", Ok(Kibi)),
             #[cfg(feature = "case-insensitive")] ("KI", Ok(Kibi)),
             #[cfg(feature = "case-insensitive")] ("mI", Ok(Mebi)),
@@ -530,10 +498,10 @@ mod tests {
             #[cfg(feature = "case-insensitive")] ("PI", Ok(Pebi)),
             #[cfg(feature = "case-insensitive")] ("eI", Ok(Exbi)),
             #[cfg(feature = "case-insensitive")] ("EI", Ok(Exbi)),
-            #[cfg(feature = "case-insensitive")] #[cfg(feature = "u128")] ("zI", Ok(Zebi)),
-            #[cfg(feature = "case-insensitive")] #[cfg(feature = "u128")] ("ZI", Ok(Zebi)),
-            #[cfg(feature = "case-insensitive")] #[cfg(feature = "u128")] ("yI", Ok(Yobi)),
-            #[cfg(feature = "case-insensitive")] #[cfg(feature = "u128")] ("YI", Ok(Yobi)),
+            #[cfg(feature = "case-insensitive")] ("zI", Ok(Zebi)),
+            #[cfg(feature = "case-insensitive")] ("ZI", Ok(Zebi)),
+            #[cfg(feature = "case-insensitive")] ("yI", Ok(Yobi)),
+            #[cfg(feature = "case-insensitive")] ("YI", Ok(Yobi)),
         ];
 
         assert_eq!(Err(ParseError::EmptyInput), "".parse::<UnitPrefix>());
@@ -548,10 +516,10 @@ mod tests {
             let invalid_formats = [
                      "ki", "m", "mi", "g", "gi",
                 "t", "ti", "p", "pi", "e", "ei",
-                #[cfg(feature = "u128")] "z" ,
-                #[cfg(feature = "u128")] "zi",
-                #[cfg(feature = "u128")] "y" ,
-                #[cfg(feature = "u128")] "yi",
+                "z" ,
+                "zi",
+                "y" ,
+                "yi",
             ];
 
             for value in invalid_formats.iter() {
@@ -565,10 +533,10 @@ mod tests {
             let invalid_prefixes = [
                 "kI", "KI", "mI", "MI", "gI", "GI",
                 "tI", "TI", "pI", "PI", "eI", "EI",
-                #[cfg(feature = "u128")] "zI" ,
-                #[cfg(feature = "u128")] "ZI",
-                #[cfg(feature = "u128")] "yI" ,
-                #[cfg(feature = "u128")] "YI",
+                "zI" ,
+                "ZI",
+                "yI" ,
+                "YI",
             ];
 
             for value in invalid_prefixes.iter() {
@@ -587,10 +555,10 @@ mod tests {
             (Tera, 1000000000000),        (Tebi, 1099511627776),
             (Peta, 1000000000000000),     (Pebi, 1125899906842624),
             (Exa , 1000000000000000000),  (Exbi, 1152921504606846976),
-            #[cfg(feature = "u128")] (Zetta, 1000000000000000000000),
-            #[cfg(feature = "u128")] (Yotta, 1000000000000000000000000),
-            #[cfg(feature = "u128")] (Zebi , 1180591620717411303424),
-            #[cfg(feature = "u128")] (Yobi , 1208925819614629174706176)
+            (Zetta, 1000000000000000000000),
+            (Yotta, 1000000000000000000000000),
+            (Zebi , 1180591620717411303424),
+            (Yobi , 1208925819614629174706176)
         ];
 
         for (prefix, value) in map.iter() {
@@ -607,9 +575,6 @@ mod tests {
     #[test]
     fn min_max() {
         assert_eq!(Kilo, UnitPrefix::MIN);
-        #[cfg(feature = "u128")]
         assert_eq!(Yobi, UnitPrefix::MAX);
-        #[cfg(not(feature = "u128"))]
-        assert_eq!(Exbi, UnitPrefix::MAX);
     }
 }
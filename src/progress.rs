@@ -0,0 +1,254 @@
+use super::{bytesize::ReprConfig, ByteSize, Mode, ReprFormat};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::fmt;
+
+#[derive(Eq, Copy, Clone, Debug, PartialEq)]
+pub enum BarStyle {
+    /// `[=====>    ]`
+    Ascii,
+    /// `[█████▍    ]`, with eighth-block resolution
+    Unicode,
+}
+
+use BarStyle::*;
+
+impl BarStyle {
+    pub const fn fill(&self) -> char {
+        match self {
+            Ascii => '=',
+            Unicode => '█',
+        }
+    }
+
+    pub const fn empty(&self) -> char {
+        ' '
+    }
+
+    // partial cell for `eighths` in 1..=7
+    const fn partial(&self, eighths: usize) -> char {
+        match self {
+            Ascii => '>',
+            Unicode => ['▏', '▎', '▍', '▌', '▋', '▊', '▉'][eighths - 1],
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ProgressBar {
+    width: usize,
+    style: BarStyle,
+    mode: Mode,
+    format: ReprFormat,
+    drawn: usize,
+}
+
+impl ProgressBar {
+    /// Create a progress bar whose bar segment is `width` cells wide
+    ///
+    /// ```
+    /// use xbytes::{prelude::*, ProgressBar};
+    ///
+    /// let bar = ProgressBar::new(10);
+    /// let line = bar.render(ByteSize::of(4.5, MEBI_BYTE), ByteSize::of(10, MEBI_BYTE));
+    /// assert_eq!(line.to_string(), "[====>     ] 4.50 MiB / 10 MiB  45%");
+    /// ```
+    pub const fn new(width: usize) -> Self {
+        Self {
+            width,
+            style: BarStyle::Ascii,
+            mode: Mode::Default,
+            format: ReprFormat::default(),
+            drawn: 0,
+        }
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn with_style(&self, style: BarStyle) -> Self {
+        Self { style, ..*self }
+    }
+
+    pub fn with_mode(&self, mode: Mode) -> Self {
+        Self { mode, ..*self }
+    }
+
+    pub fn with(&self, conf: impl ReprConfig) -> Self {
+        Self {
+            format: self.format.with(conf),
+            ..*self
+        }
+    }
+
+    pub fn render(&self, current: ByteSize, total: ByteSize) -> ProgressLine {
+        ProgressLine {
+            bar: *self,
+            current,
+            total,
+        }
+    }
+
+    /// Render a line meant to overwrite the previously drawn one
+    ///
+    /// The line is prefixed with a carriage return and padded with
+    /// enough spaces to blank out any leftovers of a longer previous line.
+    pub fn redraw(&mut self, current: ByteSize, total: ByteSize) -> String {
+        let line = self.render(current, total).to_string();
+        let len = line.chars().count();
+        let padding = self.drawn.saturating_sub(len);
+        self.drawn = len;
+        format!("\r{}{}", line, " ".repeat(padding))
+    }
+
+    /// Blank out the previously drawn line, leaving the cursor at its start
+    pub fn clear(&mut self) -> String {
+        let cleared = format!("\r{}\r", " ".repeat(self.drawn));
+        self.drawn = 0;
+        cleared
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ProgressLine {
+    bar: ProgressBar,
+    current: ByteSize,
+    total: ByteSize,
+}
+
+impl ProgressLine {
+    pub fn ratio(&self) -> f64 {
+        if self.total.0 == 0 {
+            return 1.0;
+        }
+        (self.current.0 as f64 / self.total.0 as f64).clamp(0.0, 1.0)
+    }
+}
+
+impl fmt::Display for ProgressLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ProgressBar {
+            width,
+            style,
+            mode,
+            format,
+            ..
+        } = self.bar;
+        let ratio = self.ratio();
+        let eighths = (ratio * (width * 8) as f64) as usize;
+        let (full, partial) = (eighths / 8, eighths % 8);
+
+        f.write_str("[")?;
+        for _ in 0..full {
+            write!(f, "{}", style.fill())?;
+        }
+        let mut drawn = full;
+        if partial > 0 && drawn < width {
+            write!(f, "{}", style.partial(partial))?;
+            drawn += 1;
+        }
+        for _ in drawn..width {
+            write!(f, "{}", style.empty())?;
+        }
+        write!(
+            f,
+            "] {} / {} {:>3}%",
+            self.current.repr(mode).with(format),
+            self.total.repr(mode).with(format),
+            (ratio * 100.0) as u8
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::sizes::*, *};
+    use crate::{Format, ReprConfigVariant::*};
+
+    #[test]
+    fn render_ascii() {
+        let bar = ProgressBar::new(10);
+        let total = ByteSize::of(10, MEBI_BYTE);
+
+        assert_eq!(
+            "[          ] 0 B / 10 MiB   0%",
+            bar.render(ByteSize::of(0, BYTE), total).to_string()
+        );
+        assert_eq!(
+            "[===>      ] 3.50 MiB / 10 MiB  35%",
+            bar.render(ByteSize::of(3.5, MEBI_BYTE), total).to_string()
+        );
+        assert_eq!(
+            "[==========] 10 MiB / 10 MiB 100%",
+            bar.render(total, total).to_string()
+        );
+    }
+
+    #[test]
+    fn render_unicode() {
+        let bar = ProgressBar::new(4).with_style(BarStyle::Unicode);
+        let total = ByteSize::of(32, KIBI_BYTE);
+
+        assert_eq!(
+            "[▏   ] 1 KiB / 32 KiB   3%",
+            bar.render(ByteSize::of(1, KIBI_BYTE), total).to_string()
+        );
+        assert_eq!(
+            "[██▌ ] 20 KiB / 32 KiB  62%",
+            bar.render(ByteSize::of(20, KIBI_BYTE), total).to_string()
+        );
+        assert_eq!(
+            "[███▉] 31 KiB / 32 KiB  96%",
+            bar.render(ByteSize::of(31, KIBI_BYTE), total).to_string()
+        );
+    }
+
+    #[test]
+    fn render_overflow_and_empty_total() {
+        let bar = ProgressBar::new(4);
+
+        assert_eq!(
+            "[====] 2 KiB / 1 KiB 100%",
+            bar.render(ByteSize::of(2, KIBI_BYTE), ByteSize::of(1, KIBI_BYTE))
+                .to_string()
+        );
+        assert_eq!(
+            "[====] 0 B / 0 B 100%",
+            bar.render(ByteSize::of(0, BYTE), ByteSize::of(0, BYTE))
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn render_with_format() {
+        let bar = ProgressBar::new(2)
+            .with_mode(Mode::Decimal)
+            .with(Format::NoSpace)
+            .with(Precision(1));
+
+        assert_eq!(
+            "[= ] 1.5MB / 3MB  50%",
+            bar.render(ByteSize::of(1.5, MEGA_BYTE), ByteSize::of(3, MEGA_BYTE))
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn redraw_in_place() {
+        let mut bar = ProgressBar::new(2);
+
+        assert_eq!(
+            "\r[= ] 512 KiB / 1 MiB  50%",
+            bar.redraw(ByteSize::of(512, KIBI_BYTE), ByteSize::of(1, MEBI_BYTE))
+        );
+        assert_eq!(
+            "\r[==] 1 MiB / 1 MiB 100%  ",
+            bar.redraw(ByteSize::of(1, MEBI_BYTE), ByteSize::of(1, MEBI_BYTE))
+        );
+        assert_eq!(format!("\r{}\r", " ".repeat(23)), bar.clear());
+        assert_eq!("\r\r", bar.clear());
+    }
+}
@@ -0,0 +1,146 @@
+//! [`proptest`] strategies
+//!
+//! Mirrors the [`Arbitrary`] implementations,
+//! so sizes, units, modes and formats work with `any::<T>()`. Sizes are spread
+//! log-uniformly across every scale the backing integer can hold, while valid inputs
+//! go up to thousands of yottabytes, so only a `ByteSize<u128>` holds them all.
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use xbytes::{proptest::valid_input, ByteSize};
+//!
+//! proptest!(|(size in any::<ByteSize>(), input in valid_input())| {
+//!     prop_assert!(input.parse::<ByteSize<u128>>().is_ok());
+//!     prop_assert_eq!(Ok(size), size.to_string_exact().parse());
+//! });
+//! ```
+
+use super::{sample, Backing, BitSize, ByteSize, Format, Int, Mode, SizeVariant, Unit, UnitPrefix};
+use ::proptest::{
+    arbitrary::{any, Arbitrary},
+    sample::select,
+    strategy::{BoxedStrategy, Strategy},
+};
+
+fn scaled(per_unit: fn(&Unit) -> Int, max: Int) -> impl Strategy<Value = Int> {
+    select(sample::scales(per_unit, max)).prop_flat_map(move |scale| {
+        (0..1024 as Int, any::<Int>())
+            .prop_map(move |(count, rem)| sample::scaled(scale, count, rem, max))
+    })
+}
+
+/// Byte sizes of any scale that fits `T`
+pub fn byte_size<T: Backing>() -> impl Strategy<Value = ByteSize<T>> {
+    scaled(|unit| unit.effective_value() / 8, T::MAX.into())
+        .prop_map(|bytes| ByteSize(T::saturating_from(bytes)))
+}
+
+/// Bit sizes of any scale that fits `T`
+pub fn bit_size<T: Backing>() -> impl Strategy<Value = BitSize<T>> {
+    scaled(Unit::effective_value, T::MAX.into()).prop_map(|bits| BitSize(T::saturating_from(bits)))
+}
+
+pub fn unit() -> impl Strategy<Value = Unit> {
+    select(&super::sizes::ALL[..])
+}
+
+pub fn unit_prefix() -> impl Strategy<Value = UnitPrefix> {
+    select(&UnitPrefix::ALL[..])
+}
+
+pub fn size_variant() -> impl Strategy<Value = SizeVariant> {
+    select(&[SizeVariant::Bit, SizeVariant::Byte][..])
+}
+
+/// Any combination of [`Mode`] flags
+pub fn mode() -> impl Strategy<Value = Mode> {
+    any::<u8>().prop_map(Mode::from_bits_truncate)
+}
+
+/// Any combination of [`Format`] flags
+pub fn format() -> impl Strategy<Value = Format> {
+    any::<u16>().prop_map(Format::from_bits_truncate)
+}
+
+/// Human readable sizes that parse into a `ByteSize<u128>`, like `"1,234.5 KiloBytes"`
+pub fn valid_input() -> impl Strategy<Value = String> {
+    (
+        0..10_000_000u32,
+        any::<Option<u16>>(),
+        any::<bool>(),
+        0..=2usize,
+        unit(),
+        any::<Option<(bool, bool)>>(),
+    )
+        .prop_map(|(whole, fract, grouped, spaces, unit, long)| {
+            sample::valid_input(whole, fract, grouped, spaces, unit, long)
+        })
+}
+
+/// Human readable sizes that fail to parse, like `"1,23 KiB"`
+pub fn invalid_input() -> impl Strategy<Value = String> {
+    (any::<u8>(), 0..10_000_000u32, unit())
+        .prop_map(|(kind, whole, unit)| sample::invalid_input(kind, whole, unit))
+}
+
+macro_rules! impl_arbitrary {
+    ($($type:ty => $strategy:expr),+ $(,)?) => {
+        $(
+            impl Arbitrary for $type {
+                type Parameters = ();
+                type Strategy = BoxedStrategy<Self>;
+
+                fn arbitrary_with(_: ()) -> Self::Strategy {
+                    $strategy.boxed()
+                }
+            }
+        )+
+    };
+}
+
+impl<T: Backing> Arbitrary for ByteSize<T> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        byte_size().boxed()
+    }
+}
+
+impl<T: Backing> Arbitrary for BitSize<T> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        bit_size().boxed()
+    }
+}
+
+impl_arbitrary! {
+    Unit => unit(),
+    UnitPrefix => unit_prefix(),
+    SizeVariant => size_variant(),
+    Mode => mode(),
+    Format => format(),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::proptest::{prop_assert, prop_assert_eq, proptest};
+
+    proptest! {
+        #[test]
+        fn inputs(valid in valid_input(), invalid in invalid_input()) {
+            prop_assert!(valid.parse::<ByteSize<u128>>().is_ok(), "{:?}", valid);
+            prop_assert!(invalid.parse::<ByteSize>().is_err(), "{:?}", invalid);
+        }
+
+        #[test]
+        fn sizes(size in any::<ByteSize<u32>>(), unit in any::<Unit>()) {
+            prop_assert!(size <= ByteSize(u32::MAX));
+            prop_assert_eq!(Ok(size), size.to_string_exact().parse());
+            prop_assert_eq!(Ok(unit), unit.to_string().parse());
+        }
+    }
+}
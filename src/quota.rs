@@ -0,0 +1,293 @@
+use super::{bytesize::split_list, ByteSize, ParseError};
+use core::{fmt, str::FromStr};
+
+#[derive(Eq, Copy, Clone, Debug, PartialEq)]
+pub enum QuotaStatus {
+    Within,
+    /// usage is past the soft limit, but still within the hard limit
+    SoftExceeded,
+}
+
+/// A reservation that would have taken usage past the hard limit
+#[derive(Eq, Copy, Clone, Debug, PartialEq)]
+pub struct QuotaExceeded {
+    requested: ByteSize,
+    remaining: ByteSize,
+}
+
+impl QuotaExceeded {
+    pub const fn requested(&self) -> ByteSize {
+        self.requested
+    }
+
+    pub const fn remaining(&self) -> ByteSize {
+        self.remaining
+    }
+}
+
+impl fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "quota exceeded: requested {}, only {} remaining",
+            self.requested, self.remaining
+        )
+    }
+}
+
+impl core::error::Error for QuotaExceeded {}
+
+/// Usage tracked against a soft and a hard limit
+///
+/// ```
+/// use xbytes::{prelude::*, Quota, QuotaStatus};
+///
+/// let mut quota = "50 GiB soft, 55 GiB hard".parse::<Quota>().unwrap();
+///
+/// assert_eq!(quota.reserve(ByteSize::of(48, GIBI_BYTE)), Ok(QuotaStatus::Within));
+/// assert_eq!(quota.reserve(ByteSize::of(4, GIBI_BYTE)), Ok(QuotaStatus::SoftExceeded));
+/// assert_eq!(quota.overage().to_string(), "2 GiB");
+/// assert_eq!(quota.remaining().to_string(), "3 GiB");
+/// assert!(quota.reserve(ByteSize::of(4, GIBI_BYTE)).is_err());
+///
+/// quota.release(ByteSize::of(10, GIBI_BYTE));
+/// assert_eq!(quota.status(), QuotaStatus::Within);
+/// ```
+#[derive(Eq, Copy, Clone, Debug, PartialEq)]
+pub struct Quota {
+    soft: ByteSize,
+    hard: ByteSize,
+    used: ByteSize,
+}
+
+impl Quota {
+    /// Create a quota, clamping the soft limit to the hard limit
+    pub fn new(soft: ByteSize, hard: ByteSize) -> Self {
+        Self {
+            soft: soft.min(hard),
+            hard,
+            used: ByteSize(0),
+        }
+    }
+
+    pub const fn soft_limit(&self) -> ByteSize {
+        self.soft
+    }
+
+    pub const fn hard_limit(&self) -> ByteSize {
+        self.hard
+    }
+
+    pub const fn used(&self) -> ByteSize {
+        self.used
+    }
+
+    /// Headroom left before reaching the hard limit
+    pub fn remaining(&self) -> ByteSize {
+        ByteSize(self.hard.0.saturating_sub(self.used.0))
+    }
+
+    /// Headroom left before reaching the soft limit
+    pub fn soft_remaining(&self) -> ByteSize {
+        ByteSize(self.soft.0.saturating_sub(self.used.0))
+    }
+
+    /// Amount of usage past the soft limit
+    pub fn overage(&self) -> ByteSize {
+        ByteSize(self.used.0.saturating_sub(self.soft.0))
+    }
+
+    pub fn status(&self) -> QuotaStatus {
+        if self.used > self.soft {
+            QuotaStatus::SoftExceeded
+        } else {
+            QuotaStatus::Within
+        }
+    }
+
+    /// Account for `amount` of new usage, unless it would exceed the hard limit
+    pub fn reserve(&mut self, amount: ByteSize) -> Result<QuotaStatus, QuotaExceeded> {
+        let remaining = self.remaining();
+        if amount > remaining {
+            return Err(QuotaExceeded {
+                requested: amount,
+                remaining,
+            });
+        }
+        self.used.0 += amount.0;
+        Ok(self.status())
+    }
+
+    /// Give back `amount` of previously reserved usage
+    pub fn release(&mut self, amount: ByteSize) {
+        self.used.0 = self.used.0.saturating_sub(amount.0);
+    }
+}
+
+impl fmt::Display for Quota {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} soft, {} hard", self.soft, self.hard)
+    }
+}
+
+/// A quota that failed to parse
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum QuotaError {
+    /// a limit that isn't a valid size
+    Parse(ParseError),
+    /// a limit given twice, or a soft limit past the hard one
+    InvalidFormat,
+}
+
+impl From<ParseError> for QuotaError {
+    fn from(err: ParseError) -> Self {
+        QuotaError::Parse(err)
+    }
+}
+
+impl fmt::Display for QuotaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuotaError::Parse(err) => fmt::Display::fmt(err, f),
+            QuotaError::InvalidFormat => {
+                f.pad("invalid quota format: expected format like '50 GiB soft, 55 GiB hard'")
+            }
+        }
+    }
+}
+
+impl core::error::Error for QuotaError {}
+
+impl FromStr for Quota {
+    type Err = QuotaError;
+
+    /// Parse limits like `50 GiB soft, 55 GiB hard`
+    ///
+    /// Either limit may be omitted, in which case it takes the value of the other.
+    /// A size without a qualifier is taken as the hard limit.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err(ParseError::EmptyInput.into());
+        }
+        let (mut soft, mut hard) = (None, None);
+        // every limit ends in a qualifier or a unit, thousands separators follow digits
+        for part in split_list(s, |before, _| {
+            before.trim_end().ends_with(char::is_alphabetic)
+        }) {
+            let part = part.trim();
+            let (limit, slot) = match part.rsplit_once(char::is_whitespace) {
+                Some((size, "soft")) => (size, &mut soft),
+                Some((size, "hard")) => (size, &mut hard),
+                _ => (part, &mut hard),
+            };
+            if slot
+                .replace(limit.trim_end().parse::<ByteSize>()?)
+                .is_some()
+            {
+                return Err(QuotaError::InvalidFormat);
+            }
+        }
+        match (soft, hard) {
+            (Some(soft), Some(hard)) if soft > hard => Err(QuotaError::InvalidFormat),
+            (Some(soft), Some(hard)) => Ok(Quota::new(soft, hard)),
+            (Some(limit), None) | (None, Some(limit)) => Ok(Quota::new(limit, limit)),
+            (None, None) => Err(ParseError::EmptyInput.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::sizes::*, *};
+
+    #[test]
+    fn parse_quota() {
+        let map = [
+            (
+                "50 GiB soft, 55 GiB hard",
+                Ok(((50., GIBI_BYTE), (55., GIBI_BYTE))),
+            ),
+            (
+                "55 GiB hard, 50 GiB soft",
+                Ok(((50., GIBI_BYTE), (55., GIBI_BYTE))),
+            ),
+            (
+                "50GiB soft,55GiB hard",
+                Ok(((50., GIBI_BYTE), (55., GIBI_BYTE))),
+            ),
+            (
+                "1,024 GiB soft, 2,048 GiB hard",
+                Ok(((1., TEBI_BYTE), (2., TEBI_BYTE))),
+            ),
+            (
+                "2,048 GiB, 1,024 GiB soft",
+                Ok(((1., TEBI_BYTE), (2., TEBI_BYTE))),
+            ),
+            ("1.5 TB", Ok(((1.5, TERA_BYTE), (1.5, TERA_BYTE)))),
+            ("10 MiB soft", Ok(((10., MEBI_BYTE), (10., MEBI_BYTE)))),
+            ("10 MiB hard", Ok(((10., MEBI_BYTE), (10., MEBI_BYTE)))),
+            ("", Err(QuotaError::Parse(ParseError::EmptyInput))),
+            ("10 MiB soft, 5 MiB hard", Err(QuotaError::InvalidFormat)),
+            ("10 MiB hard, 20 MiB hard", Err(QuotaError::InvalidFormat)),
+            ("10 MiB, 20 MiB", Err(QuotaError::InvalidFormat)),
+            (
+                "10 MiB firm",
+                Err(QuotaError::Parse(ParseError::InvalidSizeVariant)),
+            ),
+        ];
+
+        for (input, expected) in map.iter() {
+            assert_eq!(
+                expected.map(|((soft, soft_unit), (hard, hard_unit))| Quota::new(
+                    ByteSize::of(soft, soft_unit),
+                    ByteSize::of(hard, hard_unit)
+                )),
+                input.parse::<Quota>(),
+                "while parsing {:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn display_quota() {
+        let quota = "50 GiB soft, 55 GiB hard".parse::<Quota>().unwrap();
+        assert_eq!("50 GiB soft, 55 GiB hard", quota.to_string());
+        assert_eq!(Ok(quota), quota.to_string().parse());
+    }
+
+    #[test]
+    fn reserve_and_release() {
+        let mut quota = Quota::new(ByteSize::of(1, MEBI_BYTE), ByteSize::of(2, MEBI_BYTE));
+
+        assert_eq!(
+            Ok(QuotaStatus::Within),
+            quota.reserve(ByteSize::of(1, MEBI_BYTE))
+        );
+        assert_eq!(ByteSize::of(0, BYTE), quota.soft_remaining());
+        assert_eq!(ByteSize::of(0, BYTE), quota.overage());
+
+        assert_eq!(
+            Ok(QuotaStatus::SoftExceeded),
+            quota.reserve(ByteSize::of(512, KIBI_BYTE))
+        );
+        assert_eq!("512 KiB", quota.overage().to_string());
+        assert_eq!("512 KiB", quota.remaining().to_string());
+
+        let err = quota.reserve(ByteSize::of(1, MEBI_BYTE)).unwrap_err();
+        assert_eq!(ByteSize::of(1, MEBI_BYTE), err.requested());
+        assert_eq!(ByteSize::of(512, KIBI_BYTE), err.remaining());
+        assert_eq!(
+            "quota exceeded: requested 1 MiB, only 512 KiB remaining",
+            err.to_string()
+        );
+        assert_eq!(ByteSize::of(1.5, MEBI_BYTE), quota.used());
+
+        quota.release(ByteSize::of(1, MEBI_BYTE));
+        assert_eq!(QuotaStatus::Within, quota.status());
+        assert_eq!(ByteSize::of(512, KIBI_BYTE), quota.used());
+
+        quota.release(ByteSize::of(1, GIBI_BYTE));
+        assert_eq!(ByteSize::of(0, BYTE), quota.used());
+    }
+}
@@ -0,0 +1,464 @@
+use super::{bytesize::split_list, gcd, ByteSize, Float, Int, ParseError};
+use core::{
+    fmt,
+    ops::{Bound, RangeBounds},
+    str::FromStr,
+};
+
+/// A range of sizes, like `1 MiB..64 MiB` or `<= 2 GiB`
+///
+/// Either bound can be inclusive, exclusive or left open.
+///
+/// ```
+/// use xbytes::{prelude::*, ByteSizeRange};
+///
+/// let uploads = "1 MiB..64 MiB".parse::<ByteSizeRange>().unwrap();
+/// assert!(uploads.contains(ByteSize::of(10, MEBI_BYTE)));
+/// assert!(!uploads.contains(ByteSize::of(64, MEBI_BYTE)));
+/// assert_eq!(uploads.clamp(ByteSize::of(1, KIBI_BYTE)), ByteSize::of(1, MEBI_BYTE));
+///
+/// let limit = "<= 2 GiB".parse::<ByteSizeRange>().unwrap();
+/// let both = uploads.intersection(&limit).unwrap();
+/// assert_eq!(both, uploads);
+///
+/// let range = "512 MiB..=2 GiB".parse::<ByteSizeRange>().unwrap();
+/// assert_eq!("512 MiB..=2048 MiB", range.to_string());
+/// ```
+#[derive(Eq, Copy, Clone, Debug, PartialEq)]
+pub struct ByteSizeRange {
+    start: Bound<ByteSize>,
+    end: Bound<ByteSize>,
+}
+
+impl ByteSizeRange {
+    pub const fn new(start: Bound<ByteSize>, end: Bound<ByteSize>) -> Self {
+        Self { start, end }
+    }
+
+    /// A range with no bounds, containing every size
+    pub const fn full() -> Self {
+        Self::new(Bound::Unbounded, Bound::Unbounded)
+    }
+
+    // the smallest size within the range, if any
+    fn first(&self) -> Option<ByteSize> {
+        match self.start {
+            Bound::Included(start) => Some(start),
+            Bound::Excluded(start) => start.0.checked_add(1).map(ByteSize),
+            Bound::Unbounded => Some(ByteSize(0)),
+        }
+    }
+
+    // the largest size within the range, if any
+    fn last(&self) -> Option<ByteSize> {
+        match self.end {
+            Bound::Included(end) => Some(end),
+            Bound::Excluded(end) => end.0.checked_sub(1).map(ByteSize),
+            Bound::Unbounded => Some(ByteSize(u64::MAX)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match (self.first(), self.last()) {
+            (Some(first), Some(last)) => first > last,
+            _ => true,
+        }
+    }
+
+    pub fn contains(&self, size: ByteSize) -> bool {
+        RangeBounds::contains(self, &size)
+    }
+
+    /// The nearest size within the range
+    ///
+    /// Sizes past a bound are moved onto it, or just inside it when it's exclusive.
+    /// An empty range clamps everything to its upper end.
+    pub fn clamp(&self, size: ByteSize) -> ByteSize {
+        let size = self.first().map_or(size, |first| size.max(first));
+        self.last().map_or(size, |last| size.min(last))
+    }
+
+    /// The sizes within both ranges, if any
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if self.is_empty() || other.is_empty() {
+            return None;
+        }
+        let start = if self.first() >= other.first() {
+            self.start
+        } else {
+            other.start
+        };
+        let end = if self.last() <= other.last() {
+            self.end
+        } else {
+            other.end
+        };
+        Some(Self::new(start, end)).filter(|range| !range.is_empty())
+    }
+}
+
+impl Default for ByteSizeRange {
+    fn default() -> Self {
+        Self::full()
+    }
+}
+
+impl RangeBounds<ByteSize> for ByteSizeRange {
+    fn start_bound(&self) -> Bound<&ByteSize> {
+        self.start.as_ref()
+    }
+
+    fn end_bound(&self) -> Bound<&ByteSize> {
+        self.end.as_ref()
+    }
+}
+
+impl From<core::ops::Range<ByteSize>> for ByteSizeRange {
+    fn from(range: core::ops::Range<ByteSize>) -> Self {
+        Self::new(Bound::Included(range.start), Bound::Excluded(range.end))
+    }
+}
+
+impl From<core::ops::RangeInclusive<ByteSize>> for ByteSizeRange {
+    fn from(range: core::ops::RangeInclusive<ByteSize>) -> Self {
+        let (start, end) = range.into_inner();
+        Self::new(Bound::Included(start), Bound::Included(end))
+    }
+}
+
+impl fmt::Display for ByteSizeRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // both bounds in the largest unit that fits each a whole number of times
+        let bounds = [self.start, self.end].map(|bound| match bound {
+            Bound::Included(size) | Bound::Excluded(size) => Some(Int::from(size.0)),
+            Bound::Unbounded => None,
+        });
+        if bounds == [None, None] {
+            return f.write_str("..");
+        }
+        let unit = ByteSize(bounds.iter().flatten().fold(0, |a, b| gcd(a, *b)) as u64)
+            .repr_exact()
+            .unit();
+        // `Float` can't hold every byte count exactly without `lossless`
+        let exact = bounds
+            .iter()
+            .flatten()
+            .all(|bytes| i!(f!(*bytes)) == *bytes);
+        let bound = |f: &mut fmt::Formatter<'_>, size: ByteSize| match exact {
+            true => fmt::Display::fmt(&size.repr_as(unit), f),
+            false => write!(
+                f,
+                "{} {}",
+                Int::from(size.0) / (unit.effective_value() / 8),
+                unit
+            ),
+        };
+        match self.start {
+            Bound::Included(start) => {
+                bound(f, start)?;
+                f.write_str("..")?;
+            }
+            Bound::Excluded(start) => {
+                f.write_str("> ")?;
+                bound(f, start)?;
+                if self.end != Bound::Unbounded {
+                    f.write_str(", ")?;
+                }
+            }
+            Bound::Unbounded => f.write_str("..")?,
+        }
+        match (self.start, self.end) {
+            (Bound::Excluded(_), Bound::Included(end)) => {
+                f.write_str("<= ")?;
+                bound(f, end)
+            }
+            (Bound::Excluded(_), Bound::Excluded(end)) => {
+                f.write_str("< ")?;
+                bound(f, end)
+            }
+            (_, Bound::Included(end)) => {
+                f.write_str("=")?;
+                bound(f, end)
+            }
+            (_, Bound::Excluded(end)) => bound(f, end),
+            (_, Bound::Unbounded) => Ok(()),
+        }
+    }
+}
+
+/// A range that failed to parse
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RangeError {
+    /// a bound that isn't a valid size
+    Parse(ParseError),
+    /// neither a range like `1 MiB..64 MiB` nor comparisons like `<= 2 GiB`
+    InvalidFormat,
+    /// bounds no size fits between, like `64 MiB..1 MiB`
+    Empty,
+}
+
+impl From<ParseError> for RangeError {
+    fn from(err: ParseError) -> Self {
+        RangeError::Parse(err)
+    }
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeError::Parse(err) => fmt::Display::fmt(err, f),
+            RangeError::InvalidFormat => {
+                f.pad("invalid range format: expected format like '1 MiB..64 MiB' or '<= 2 GiB'")
+            }
+            RangeError::Empty => f.pad("empty range: no size fits between its bounds"),
+        }
+    }
+}
+
+impl core::error::Error for RangeError {}
+
+impl FromStr for ByteSizeRange {
+    type Err = RangeError;
+
+    /// Parse ranges like `1 MiB..64 MiB`, `1 MiB..=64 MiB`, `1 MiB..` or `..=64 MiB`,
+    /// and comparisons like `<= 2 GiB` or `> 1 MiB, < 64 MiB`
+    ///
+    /// Ranges that can't contain any size are rejected.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParseError::EmptyInput.into());
+        }
+        let size = |s: &str| s.trim().parse::<ByteSize>();
+        let range = if let Some((start, end)) = s.split_once("..") {
+            let start = match start.trim() {
+                "" => Bound::Unbounded,
+                start => Bound::Included(size(start)?),
+            };
+            let end = match end.trim() {
+                "" => Bound::Unbounded,
+                end => match end.strip_prefix('=') {
+                    Some(end) => Bound::Included(size(end)?),
+                    None => Bound::Excluded(size(end)?),
+                },
+            };
+            Self::new(start, end)
+        } else {
+            let (mut start, mut end) = (None, None);
+            for part in split_list(s, |_, after| after.trim_start().starts_with(['<', '>'])) {
+                let part = part.trim();
+                let (bound, slot) = if let Some(part) = part.strip_prefix("<=") {
+                    (Bound::Included(size(part)?), &mut end)
+                } else if let Some(part) = part.strip_prefix('<') {
+                    (Bound::Excluded(size(part)?), &mut end)
+                } else if let Some(part) = part.strip_prefix(">=") {
+                    (Bound::Included(size(part)?), &mut start)
+                } else if let Some(part) = part.strip_prefix('>') {
+                    (Bound::Excluded(size(part)?), &mut start)
+                } else {
+                    return Err(RangeError::InvalidFormat);
+                };
+                if slot.replace(bound).is_some() {
+                    return Err(RangeError::InvalidFormat);
+                }
+            }
+            Self::new(
+                start.unwrap_or(Bound::Unbounded),
+                end.unwrap_or(Bound::Unbounded),
+            )
+        };
+        if range.is_empty() {
+            return Err(RangeError::Empty);
+        }
+        Ok(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{sizes::*, Unit},
+        *,
+    };
+
+    fn of(value: u64, unit: Unit) -> ByteSize {
+        ByteSize::from_unit(value, unit).unwrap()
+    }
+
+    #[test]
+    fn parse_range() {
+        let map = [
+            (
+                "1 MiB..64 MiB",
+                Ok((
+                    Bound::Included(of(1, MEBI_BYTE)),
+                    Bound::Excluded(of(64, MEBI_BYTE)),
+                )),
+            ),
+            (
+                "1MiB..=64MiB",
+                Ok((
+                    Bound::Included(of(1, MEBI_BYTE)),
+                    Bound::Included(of(64, MEBI_BYTE)),
+                )),
+            ),
+            (
+                "1 MiB ..",
+                Ok((Bound::Included(of(1, MEBI_BYTE)), Bound::Unbounded)),
+            ),
+            (
+                "..= 2 GiB",
+                Ok((Bound::Unbounded, Bound::Included(of(2, GIBI_BYTE)))),
+            ),
+            ("..", Ok((Bound::Unbounded, Bound::Unbounded))),
+            (
+                "<= 2 GiB",
+                Ok((Bound::Unbounded, Bound::Included(of(2, GIBI_BYTE)))),
+            ),
+            (
+                "> 1 MiB, < 1.5 GiB",
+                Ok((
+                    Bound::Excluded(of(1, MEBI_BYTE)),
+                    Bound::Excluded(of(1536, MEBI_BYTE)),
+                )),
+            ),
+            (
+                "> 1,024 KiB, < 2,048 KiB",
+                Ok((
+                    Bound::Excluded(of(1, MEBI_BYTE)),
+                    Bound::Excluded(of(2, MEBI_BYTE)),
+                )),
+            ),
+            (
+                "1,024 KiB..2,048 KiB",
+                Ok((
+                    Bound::Included(of(1, MEBI_BYTE)),
+                    Bound::Excluded(of(2, MEBI_BYTE)),
+                )),
+            ),
+            (
+                ">=1 kB",
+                Ok((Bound::Included(of(1, KILO_BYTE)), Bound::Unbounded)),
+            ),
+            ("", Err(RangeError::Parse(ParseError::EmptyInput))),
+            ("64 MiB..1 MiB", Err(RangeError::Empty)),
+            ("1 MiB..1 MiB", Err(RangeError::Empty)),
+            ("< 0 B", Err(RangeError::Empty)),
+            ("1 MiB", Err(RangeError::InvalidFormat)),
+            ("< 1 MiB, <= 2 MiB", Err(RangeError::InvalidFormat)),
+            ("1 MiB..64", Err(RangeError::Parse(ParseError::MissingUnit))),
+            (
+                "..=64 XB",
+                Err(RangeError::Parse(ParseError::InvalidPrefix)),
+            ),
+        ];
+
+        for (value, range) in map {
+            assert_eq!(
+                range.map(|(start, end)| ByteSizeRange::new(start, end)),
+                value.parse::<ByteSizeRange>(),
+                "expected [{}] to parse as {:?}",
+                value,
+                range
+            );
+        }
+    }
+
+    #[test]
+    fn contains_and_clamp() {
+        let range = ByteSizeRange::from(of(1, MEBI_BYTE)..of(64, MEBI_BYTE));
+        assert!(range.contains(of(1, MEBI_BYTE)));
+        assert!(!range.contains(of(64, MEBI_BYTE)));
+        assert_eq!(of(1, MEBI_BYTE), range.clamp(ByteSize(0)));
+        assert_eq!(
+            ByteSize(64 * 1024 * 1024 - 1),
+            range.clamp(of(1, GIBI_BYTE))
+        );
+        assert_eq!(of(2, MEBI_BYTE), range.clamp(of(2, MEBI_BYTE)));
+
+        let range = "> 1 KiB".parse::<ByteSizeRange>().unwrap();
+        assert_eq!(ByteSize(1025), range.clamp(ByteSize(0)));
+        assert_eq!(ByteSize(u64::MAX), range.clamp(ByteSize(u64::MAX)));
+        assert!(ByteSizeRange::full().contains(ByteSize(0)));
+        assert!(
+            ByteSizeRange::new(Bound::Excluded(ByteSize(u64::MAX)), Bound::Unbounded).is_empty()
+        );
+    }
+
+    #[test]
+    fn intersection() {
+        let uploads = "1 MiB..64 MiB".parse::<ByteSizeRange>().unwrap();
+        let limit = "<= 2 GiB".parse::<ByteSizeRange>().unwrap();
+        assert_eq!(Some(uploads), uploads.intersection(&limit));
+        assert_eq!(Some(uploads), limit.intersection(&uploads));
+
+        let large = "> 32 MiB".parse::<ByteSizeRange>().unwrap();
+        assert_eq!(
+            Some(ByteSizeRange::new(
+                Bound::Excluded(of(32, MEBI_BYTE)),
+                Bound::Excluded(of(64, MEBI_BYTE))
+            )),
+            uploads.intersection(&large)
+        );
+        assert_eq!(
+            Some(ByteSizeRange::full()),
+            ByteSizeRange::full().intersection(&ByteSizeRange::full())
+        );
+        assert_eq!(
+            None,
+            uploads.intersection(&"64 MiB..".parse::<ByteSizeRange>().unwrap())
+        );
+        let empty = ByteSizeRange::new(Bound::Excluded(ByteSize(u64::MAX)), Bound::Unbounded);
+        assert_eq!(None, empty.intersection(&ByteSizeRange::full()));
+        assert_eq!(None, ByteSizeRange::full().intersection(&empty));
+    }
+
+    #[test]
+    fn display() {
+        let map = [
+            ("1 MiB..64 MiB", "1 MiB..64 MiB"),
+            ("512 MiB..=2 GiB", "512 MiB..=2048 MiB"),
+            ("0 B..1 KiB", "0 KiB..1 KiB"),
+            ("1536 KiB..", "1536 KiB.."),
+            ("1 MiB..1025 KiB", "1024 KiB..1025 KiB"),
+            ("<= 2 GiB", "..=2 GiB"),
+            ("< 2 GiB", "..2 GiB"),
+            ("..", ".."),
+            ("> 1 KiB", "> 1 KiB"),
+            ("> 1 KiB, <= 1 MiB", "> 1 KiB, <= 1024 KiB"),
+            ("< 1 GiB, > 1 MiB", "> 1 MiB, < 1024 MiB"),
+        ];
+
+        for (value, repr) in map {
+            assert_eq!(repr, value.parse::<ByteSizeRange>().unwrap().to_string());
+        }
+        assert_eq!(
+            "1.000 MiB..64.000 MiB",
+            format!("{:.3}", "1 MiB..64 MiB".parse::<ByteSizeRange>().unwrap())
+        );
+    }
+
+    #[test]
+    fn display_round_trip() {
+        for range in [
+            ByteSizeRange::from(of(1, MEBI_BYTE)..of(1, GIBI_BYTE) + ByteSize(1)),
+            ByteSizeRange::from(of(1, MEBI_BYTE)..=of(1536, KIBI_BYTE)),
+            ByteSizeRange::from(ByteSize(1)..=ByteSize(u64::MAX)),
+            ByteSizeRange::new(
+                Bound::Excluded(of(3, KILO_BYTE)),
+                Bound::Included(ByteSize(u64::MAX)),
+            ),
+            ByteSizeRange::new(Bound::Unbounded, Bound::Excluded(ByteSize((1 << 53) + 1))),
+            ByteSizeRange::new(
+                Bound::Included(of(1, KIBI_BYTE) + ByteSize(1)),
+                Bound::Unbounded,
+            ),
+        ] {
+            assert_eq!(Ok(range), range.to_string().parse(), "{}", range);
+        }
+        assert_eq!(
+            "1048576 B..1073741825 B",
+            ByteSizeRange::from(of(1, MEBI_BYTE)..of(1, GIBI_BYTE) + ByteSize(1)).to_string()
+        );
+    }
+}
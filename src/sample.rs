@@ -0,0 +1,68 @@
+// pieces shared by the `arbitrary` and `proptest` generators
+
+use super::{sizes, Int, Unit};
+
+// one scale per unit, up to the largest that fits `max`, to spread sizes log-uniformly
+pub(crate) fn scales(per_unit: fn(&Unit) -> Int, max: Int) -> Vec<Int> {
+    sizes::BYTES
+        .iter()
+        .map(per_unit)
+        .filter(|scale| *scale <= max)
+        .collect()
+}
+
+// somewhere between one and 1024 of `scale`, saturating at `max`
+pub(crate) fn scaled(scale: Int, count: Int, rem: Int, max: Int) -> Int {
+    (count * scale + rem % scale).min(max)
+}
+
+// "1,234.5 KiloBytes"
+pub(crate) fn valid_input(
+    whole: u32,
+    fract: Option<u16>,
+    grouped: bool,
+    spaces: usize,
+    unit: Unit,
+    long: Option<(bool, bool)>,
+) -> String {
+    let digits = whole.to_string();
+    let mut input = String::new();
+    for (index, c) in digits.chars().enumerate() {
+        if grouped && index != 0 && (digits.len() - index) % 3 == 0 {
+            input.push(',');
+        }
+        input.push(c);
+    }
+    if let Some(fract) = fract {
+        input.push_str(&format!(".{}", fract));
+    }
+    input.push_str(&" ".repeat(spaces));
+    let (prefix, size_variant) = match long {
+        Some((plural, multi_caps)) => unit.symbols_long(plural, multi_caps),
+        None => unit.symbols(),
+    };
+    input.push_str(prefix);
+    input.push_str(size_variant);
+    input
+}
+
+// each kind fails for a different reason, with or without the `case-insensitive` feature
+pub(crate) fn invalid_input(kind: u8, whole: u32, unit: Unit) -> String {
+    let (prefix, size_variant) = unit.symbols();
+    match kind % 7 {
+        0 => String::new(),
+        1 => whole.to_string(),
+        2 => format!("{}{}", prefix, size_variant),
+        3 => format!("{} X{}", whole, size_variant),
+        4 => format!("{},{:02} {}{}", whole, whole % 100, prefix, size_variant),
+        5 => format!("{} {}x", whole, prefix),
+        _ => format!(
+            "{}.{}.{} {}{}",
+            whole,
+            whole % 10,
+            whole % 7,
+            prefix,
+            size_variant
+        ),
+    }
+}
@@ -0,0 +1,210 @@
+use super::{sizes, Backing, ByteSize, Unit, UnitPrefix};
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use std::borrow::Cow;
+
+// `kilo` -> `[kK][iI][lL][oO]`, since JSON schema patterns don't take flags
+fn any_case(s: &str) -> String {
+    s.chars()
+        .map(|c| format!("[{}{}]", c.to_lowercase(), c.to_uppercase()))
+        .collect()
+}
+
+fn prefix_pattern() -> String {
+    let mut prefixes = vec![];
+    for prefix in UnitPrefix::ALL {
+        #[cfg(not(feature = "case-insensitive"))]
+        prefixes.push(prefix.symbol().to_string());
+        #[cfg(feature = "case-insensitive")]
+        prefixes.push(any_case(prefix.symbol()));
+        prefixes.push(any_case(prefix.symbol_long()));
+    }
+    // `k` is the only lowercase short prefix
+    #[cfg(not(feature = "case-insensitive"))]
+    prefixes.push("k".to_string());
+    prefixes.join("|")
+}
+
+/// A pattern matching everything `ByteSize::from_str` accepts
+fn bytesize_pattern() -> String {
+    let unit = format!(
+        r"(?:{})?(?:b|B|{}[sS]?|{}[sS]?)",
+        prefix_pattern(),
+        any_case("bit"),
+        any_case("byte"),
+    );
+    // radix literals like "0x1000", "0o777 KiB" or "0b1010_1010 B" default to bytes, with
+    // single underscores between digits. Units after hex digits need a space
+    let hex = format!(r"0[xX][0-9a-fA-F]+(?:_[0-9a-fA-F]+)*(?:\s+{unit})?");
+    let radix = format!(r"0(?:[oO][0-7]+(?:_[0-7]+)*|[bB][01]+(?:_[01]+)*)(?:\s*{unit})?");
+    let decimal = format!(r"\+?(?:(?:\d{{1,3}}(?:,\d{{3}})+|\d+)(?:\.\d*)?|\.\d+)\s*{unit}");
+    format!("^(?:{hex}|{radix}|{decimal})$")
+}
+
+impl<T: Backing> JsonSchema for ByteSize<T> {
+    fn schema_name() -> Cow<'static, str> {
+        "ByteSize".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "xbytes::ByteSize".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "A size, either human readable like \"1.5 GiB\" or a number of bytes",
+            "anyOf": [
+                {
+                    "type": "string",
+                    "pattern": bytesize_pattern(),
+                    "examples": ["1.5 GiB", "1,024 KB", "512 bytes", "0x1000 KiB"],
+                },
+                {
+                    "type": "integer",
+                    "minimum": 0,
+                },
+            ],
+        })
+    }
+}
+
+impl JsonSchema for Unit {
+    fn schema_name() -> Cow<'static, str> {
+        "Unit".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "xbytes::Unit".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "A unit of size, like \"KiB\" or \"Mb\"",
+            "type": "string",
+            "enum": sizes::ALL.iter().map(Unit::symbol).collect::<Vec<_>>(),
+        })
+    }
+}
+
+impl JsonSchema for UnitPrefix {
+    fn schema_name() -> Cow<'static, str> {
+        "UnitPrefix".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "xbytes::UnitPrefix".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "A decimal or binary prefix, like \"K\" or \"Gi\"",
+            "type": "string",
+            "enum": UnitPrefix::ALL.iter().map(UnitPrefix::symbol).collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::schema_for;
+
+    #[test]
+    fn bytesize_schema() {
+        let schema = schema_for!(ByteSize);
+        let variants = schema.get("anyOf").unwrap().as_array().unwrap();
+        assert_eq!("string", variants[0]["type"]);
+        assert_eq!(bytesize_pattern(), variants[0]["pattern"]);
+        assert_eq!("integer", variants[1]["type"]);
+        for example in variants[0]["examples"].as_array().unwrap() {
+            assert!(example.as_str().unwrap().parse::<ByteSize>().is_ok());
+        }
+    }
+
+    #[test]
+    fn bytesize_pattern_matches_from_str() {
+        let pattern = regex::Regex::new(&bytesize_pattern()).unwrap();
+        for input in [
+            "1.5 GiB",
+            "1,024 KB",
+            "1,024.5 KiB",
+            "512 bytes",
+            "3 KibiBytes",
+            "16 Mb",
+            "8bits",
+            "+1 KiB",
+            ".5 KiB",
+            "1. KiB",
+            "0 b",
+            "0b",
+            "0B",
+            "0x1000 B",
+            "0x20000000",
+            "0X2000_0000",
+            "0o777 KiB",
+            "0b1010 KiB",
+            "0o777KiB",
+            "0b1b",
+            "0xab B",
+            "0xaf",
+            "0x1E",
+            "0xaB",
+            "0x1b",
+            "0x10b",
+            "0x1000B",
+            "0xb",
+            "0xa_b",
+            "0x40 Kb",
+            "0xff bytes",
+        ] {
+            assert!(input.parse::<ByteSize<u128>>().is_ok(), "{:?}", input);
+            assert!(pattern.is_match(input), "{:?}", input);
+        }
+        for input in [
+            "",
+            "KiB",
+            "16",
+            "-1 KiB",
+            " 1 KiB",
+            "1 KiB ",
+            "1,23 KiB",
+            "1_000 B",
+            "1e3 B",
+            "16 XiB",
+            "16 MiBss",
+            "0x",
+            "0x KiB",
+            "0x+1 B",
+            "0o8 KiB",
+            "0b102 B",
+            "0x1.8 KiB",
+            "0x1,000 KiB",
+            "00x10 B",
+            "0x20000000 ",
+            "0x10KiB",
+            "0x10EiB",
+            "0x1_",
+            "0x1__2",
+            "0x1_ B",
+            "0o7__7 KiB",
+            "0b1_",
+        ] {
+            assert!(input.parse::<ByteSize<u128>>().is_err(), "{:?}", input);
+            assert!(!pattern.is_match(input), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn unit_schemas() {
+        let schema = schema_for!(Unit);
+        let units = schema.get("enum").unwrap().as_array().unwrap();
+        assert_eq!(sizes::ALL.len(), units.len());
+        for unit in units {
+            assert!(unit.as_str().unwrap().parse::<Unit>().is_ok());
+        }
+
+        let schema = schema_for!(UnitPrefix);
+        let prefixes = schema.get("enum").unwrap().as_array().unwrap();
+        assert_eq!(UnitPrefix::ALL.len(), prefixes.len());
+        assert_eq!("Ki", prefixes[1]);
+    }
+}
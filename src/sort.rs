@@ -0,0 +1,236 @@
+//! Ordering human readable sizes like GNU `sort -h`
+
+use super::{ByteSize, ParseError, SizeVariant, Unit, UnitPrefix};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::cmp::Ordering;
+
+/// Parse sizes the way tools like `du -h` and `ls -h` print them
+///
+/// On top of everything [`ByteSize`] parses, this accepts a bare number
+/// as a count of bytes and a bare prefix as its binary unit, so `12K`,
+/// `1.5G` and `1.5Gi` are all binary, while `1.5GB` stays decimal.
+///
+/// ```
+/// use xbytes::{sort::parse_lenient, prelude::*};
+///
+/// assert_eq!(parse_lenient("1.5G"), Ok(ByteSize::of(1.5, GIBI_BYTE)));
+/// assert_eq!(parse_lenient("900 MB"), Ok(ByteSize::of(900, MEGA_BYTE)));
+/// assert_eq!(parse_lenient("512"), Ok(ByteSize::of(512, BYTE)));
+/// ```
+pub fn parse_lenient(s: &str) -> Result<ByteSize, ParseError> {
+    let s = s.trim();
+    let index = s
+        .find(|c: char| c.is_alphabetic() || c.is_whitespace())
+        .unwrap_or(s.len());
+    let (value, unit) = s.split_at(index);
+    let unit = match unit.trim_start() {
+        "" => Unit::from(SizeVariant::Byte),
+        unit => match unit.parse::<UnitPrefix>() {
+            Ok(prefix) => Unit::from(prefix.binary()),
+            Err(_) => unit.parse()?,
+        },
+    };
+    format!("{} {}", value, unit).parse()
+}
+
+/// A key ordering strings exactly like GNU `sort -h` does
+///
+/// Leading blanks are skipped, and the number that follows is ranked first
+/// by its SI suffix (none, `K`, `M`, `G`, ...) and only then by its value, so
+/// `2000M` sorts before `1.5G`. Anything that isn't a number sorts as zero.
+///
+/// Equal keys behave like `sort -s -h` when used with a stable sort such
+/// as [`slice::sort_by_key`]; [`human_cmp`] breaks ties like plain `sort -h`.
+///
+/// ```
+/// use xbytes::sort::HumanKey;
+///
+/// let mut sizes = ["1.5G", "900M", "12K", "2000M", "-1K", "7"];
+/// sizes.sort_by_key(|size| HumanKey::new(size));
+/// assert_eq!(sizes, ["-1K", "7", "12K", "900M", "2000M", "1.5G"]);
+/// ```
+#[derive(Eq, Clone, Debug, PartialEq)]
+pub struct HumanKey {
+    // suffix rank, negated for negative numbers
+    order: i8,
+    negative: bool,
+    // digits without leading zeros
+    integer: String,
+    // digits without trailing zeros
+    fraction: String,
+}
+
+impl HumanKey {
+    pub fn new(s: &str) -> Self {
+        let s = s.trim_start_matches([' ', '\t']);
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        let integer_len = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (integer, rest) = s.split_at(integer_len);
+        let (fraction, rest) = match rest.strip_prefix('.') {
+            Some(rest) => rest.split_at(
+                rest.find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len()),
+            ),
+            None => ("", rest),
+        };
+        let integer = integer.trim_start_matches('0');
+        let fraction = fraction.trim_end_matches('0');
+        let zero = integer.is_empty() && fraction.is_empty();
+        let order = match rest.chars().next() {
+            _ if zero => 0,
+            Some('K' | 'k') => 1,
+            Some('M') => 2,
+            Some('G') => 3,
+            Some('T') => 4,
+            Some('P') => 5,
+            Some('E') => 6,
+            Some('Z') => 7,
+            Some('Y') => 8,
+            Some('R') => 9,
+            Some('Q') => 10,
+            _ => 0,
+        };
+        Self {
+            order: if negative { -order } else { order },
+            negative: negative && !zero,
+            integer: integer.to_string(),
+            fraction: fraction.to_string(),
+        }
+    }
+}
+
+impl PartialOrd for HumanKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HumanKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let magnitude = || {
+            (self.integer.len(), &self.integer, &self.fraction).cmp(&(
+                other.integer.len(),
+                &other.integer,
+                &other.fraction,
+            ))
+        };
+        self.order
+            .cmp(&other.order)
+            .then_with(|| match (self.negative, other.negative) {
+                (false, false) => magnitude(),
+                (true, true) => magnitude().reverse(),
+                (negative, _) => negative.cmp(&false).reverse(),
+            })
+    }
+}
+
+/// Compare two strings like GNU `sort -h`
+///
+/// Strings with equal keys are compared byte by byte, like `sort` does as a last resort.
+///
+/// ```
+/// use xbytes::sort::human_cmp;
+///
+/// let mut lines = vec!["4.0K\tlogs", "1.2M\tcache", "16K\tconfig", "4.0K\tbin"];
+/// lines.sort_by(|a, b| human_cmp(a, b));
+/// assert_eq!(lines, ["4.0K\tbin", "4.0K\tlogs", "16K\tconfig", "1.2M\tcache"]);
+/// ```
+pub fn human_cmp(a: &str, b: &str) -> Ordering {
+    HumanKey::new(a)
+        .cmp(&HumanKey::new(b))
+        .then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::sizes::*, *};
+
+    #[test]
+    fn lenient() {
+        let map = [
+            ("12K", Ok(ByteSize::of(12, KIBI_BYTE))),
+            ("12k", Ok(ByteSize::of(12, KIBI_BYTE))),
+            ("1.5G", Ok(ByteSize::of(1.5, GIBI_BYTE))),
+            ("1.5 Gi", Ok(ByteSize::of(1.5, GIBI_BYTE))),
+            ("1.5GB", Ok(ByteSize::of(1.5, GIGA_BYTE))),
+            ("1.5GiB", Ok(ByteSize::of(1.5, GIBI_BYTE))),
+            ("8 Mb", Ok(ByteSize::of(1, MEGA_BYTE))),
+            (" 900M ", Ok(ByteSize::of(900, MEBI_BYTE))),
+            ("1,024", Ok(ByteSize::of(1, KIBI_BYTE))),
+            ("0", Ok(ByteSize::of(0, BYTE))),
+            ("", Err(ParseError::MissingValue)),
+            ("G", Err(ParseError::MissingValue)),
+            ("1.5X", Err(ParseError::InvalidSizeVariant)),
+        ];
+
+        for (input, expected) in map.iter() {
+            assert_eq!(*expected, parse_lenient(input), "while parsing {:?}", input);
+        }
+    }
+
+    #[test]
+    fn sort_h_order() {
+        // each entry sorts strictly after the previous one
+        let ordered = [
+            "-1G", "-2M", "-1M", "-900K", "-1", "-0.5", "0", "0.5", "1", "99", "1000", "1k",
+            "1.5K", "12K", "1M", "900M", "2000M", "1G", "1.5G", "1T", "1P", "1E", "1Z", "1Y", "1R",
+            "1Q",
+        ];
+        for pair in ordered.windows(2) {
+            assert_eq!(
+                Ordering::Less,
+                HumanKey::new(pair[0]).cmp(&HumanKey::new(pair[1])),
+                "{:?} < {:?}",
+                pair[0],
+                pair[1]
+            );
+        }
+    }
+
+    #[test]
+    fn sort_h_equal_keys() {
+        let equal = [
+            ("0", "-0"),
+            ("0K", "0"),
+            ("", "abc"),
+            ("abc", "0M"),
+            ("1.50K", "01.5K"),
+            ("  1K", "1K"),
+            ("1K", "1KiB"),
+            ("1KB", "1k"),
+            ("5M used", "5M\tfree"),
+        ];
+        for (a, b) in equal {
+            assert_eq!(HumanKey::new(a), HumanKey::new(b), "{:?} == {:?}", a, b);
+        }
+        assert_eq!(Ordering::Less, human_cmp("5M\tfree", "5M used"));
+    }
+
+    #[test]
+    fn sort_by_key() {
+        let mut report = vec![
+            "1.5G\t/var",
+            "900M\t/usr",
+            "12K\t/etc",
+            "4.0K\t/tmp",
+            "2.1T\t/home",
+        ];
+        report.sort_by_key(|line| HumanKey::new(line));
+        assert_eq!(
+            report,
+            [
+                "4.0K\t/tmp",
+                "12K\t/etc",
+                "900M\t/usr",
+                "1.5G\t/var",
+                "2.1T\t/home"
+            ]
+        );
+    }
+}
@@ -0,0 +1,386 @@
+//! Database column types for [`ByteSize`]
+//!
+//! With the `sqlx` or `diesel` features, [`ByteSize`] is stored as a number
+//! of bytes in a `BIGINT` column. Sizes past the range of `i64`, `ByteSize<u128>`
+//! included, can be stored in a `TEXT` column by wrapping them in [`AsText`].
+//! `NUMERIC` columns aren't mapped, cast them to text in the query to read them.
+//!
+//! Diesel needs to know the backend to write values, so storing sizes with it
+//! takes one of the `diesel-sqlite`, `diesel-postgres` or `diesel-mysql` features.
+
+use super::{Backing, ByteSize, Int, ParseError};
+use std::fmt;
+
+/// A [`ByteSize`] stored as the decimal text of its byte count
+///
+/// Covers the full range of the backing integer, `ByteSize<u128>` included, unlike the
+/// `BIGINT` mapping of [`ByteSize`] itself.
+#[derive(Eq, Ord, Copy, Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(
+    feature = "diesel",
+    derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow),
+    diesel(sql_type = diesel::sql_types::Text)
+)]
+pub struct AsText<T = u64>(pub ByteSize<T>);
+
+impl<T> From<ByteSize<T>> for AsText<T> {
+    fn from(size: ByteSize<T>) -> Self {
+        AsText(size)
+    }
+}
+
+impl<T> From<AsText<T>> for ByteSize<T> {
+    fn from(AsText(size): AsText<T>) -> Self {
+        size
+    }
+}
+
+impl<T: Backing> fmt::Display for AsText<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.whole_bytes())
+    }
+}
+
+fn from_bytes<T: Backing>(bytes: Int) -> Result<ByteSize<T>, ParseError> {
+    let bytes = T::try_from(bytes).map_err(|_| ParseError::ValueOverflow)?;
+    Ok(ByteSize(bytes))
+}
+
+// unused when diesel is enabled without any backend
+#[cfg_attr(not(feature = "sqlx"), allow(dead_code))]
+fn to_i64(size: &ByteSize) -> Result<i64, ParseError> {
+    i64::try_from(size.whole_bytes()).map_err(|_| ParseError::ValueOverflow)
+}
+
+fn from_i64(bytes: i64) -> Result<ByteSize, ParseError> {
+    from_bytes(Int::try_from(bytes).map_err(|_| ParseError::InvalidValue)?)
+}
+
+fn from_text<T: Backing>(text: &str) -> Result<ByteSize<T>, ParseError> {
+    from_bytes(
+        text.trim()
+            .parse::<Int>()
+            .map_err(|_| ParseError::InvalidValue)?,
+    )
+}
+
+#[cfg(feature = "sqlx")]
+mod sqlx_impls {
+    use super::*;
+    use sqlx::{encode::IsNull, error::BoxDynError, Database, Decode, Encode, Type};
+
+    impl<DB: Database> Type<DB> for ByteSize
+    where
+        i64: Type<DB>,
+    {
+        fn type_info() -> DB::TypeInfo {
+            <i64 as Type<DB>>::type_info()
+        }
+
+        fn compatible(ty: &DB::TypeInfo) -> bool {
+            <i64 as Type<DB>>::compatible(ty)
+        }
+    }
+
+    impl<'q, DB: Database> Encode<'q, DB> for ByteSize
+    where
+        i64: Encode<'q, DB>,
+    {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <DB as Database>::ArgumentBuffer<'q>,
+        ) -> Result<IsNull, BoxDynError> {
+            to_i64(self)?.encode_by_ref(buf)
+        }
+    }
+
+    impl<'r, DB: Database> Decode<'r, DB> for ByteSize
+    where
+        i64: Decode<'r, DB>,
+    {
+        fn decode(value: <DB as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+            Ok(from_i64(<i64 as Decode<DB>>::decode(value)?)?)
+        }
+    }
+
+    impl<T, DB: Database> Type<DB> for AsText<T>
+    where
+        String: Type<DB>,
+    {
+        fn type_info() -> DB::TypeInfo {
+            <String as Type<DB>>::type_info()
+        }
+
+        fn compatible(ty: &DB::TypeInfo) -> bool {
+            <String as Type<DB>>::compatible(ty)
+        }
+    }
+
+    impl<'q, T: Backing, DB: Database> Encode<'q, DB> for AsText<T>
+    where
+        String: Encode<'q, DB>,
+    {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <DB as Database>::ArgumentBuffer<'q>,
+        ) -> Result<IsNull, BoxDynError> {
+            self.to_string().encode_by_ref(buf)
+        }
+    }
+
+    impl<'r, T: Backing, DB: Database> Decode<'r, DB> for AsText<T>
+    where
+        &'r str: Decode<'r, DB>,
+    {
+        fn decode(value: <DB as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+            Ok(AsText(from_text(<&str as Decode<DB>>::decode(value)?)?))
+        }
+    }
+}
+
+#[cfg(feature = "diesel")]
+mod diesel_impls {
+    use super::*;
+    #[cfg(any(
+        feature = "diesel-sqlite",
+        feature = "diesel-postgres",
+        feature = "diesel-mysql"
+    ))]
+    use diesel::serialize::{self, Output, ToSql};
+    use diesel::{
+        backend::Backend,
+        deserialize::{self, FromSql},
+        sql_types::{BigInt, Text},
+    };
+
+    impl<DB: Backend> FromSql<BigInt, DB> for ByteSize
+    where
+        i64: FromSql<BigInt, DB>,
+    {
+        fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+            Ok(from_i64(i64::from_sql(bytes)?)?)
+        }
+    }
+
+    impl<T: Backing, DB: Backend> FromSql<Text, DB> for AsText<T>
+    where
+        String: FromSql<Text, DB>,
+    {
+        fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+            Ok(AsText(from_text(&String::from_sql(bytes)?)?))
+        }
+    }
+
+    // backends serializing values to bytes, the converted value only needs to outlive a reborrow
+    #[allow(unused_macros)]
+    macro_rules! impl_to_sql {
+        ($backend:ty) => {
+            impl ToSql<BigInt, $backend> for ByteSize {
+                fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, $backend>) -> serialize::Result {
+                    ToSql::<BigInt, $backend>::to_sql(&to_i64(self)?, &mut out.reborrow())
+                }
+            }
+
+            impl<T: Backing> ToSql<Text, $backend> for AsText<T> {
+                fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, $backend>) -> serialize::Result {
+                    ToSql::<Text, $backend>::to_sql(self.to_string().as_str(), &mut out.reborrow())
+                }
+            }
+        };
+    }
+
+    #[cfg(feature = "diesel-postgres")]
+    impl_to_sql!(diesel::pg::Pg);
+    #[cfg(feature = "diesel-mysql")]
+    impl_to_sql!(diesel::mysql::Mysql);
+
+    // SQLite binds values rather than bytes, so they can be handed over as is
+    #[cfg(feature = "diesel-sqlite")]
+    mod sqlite {
+        use super::*;
+        use diesel::{serialize::IsNull, sqlite::Sqlite};
+
+        impl ToSql<BigInt, Sqlite> for ByteSize {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+                out.set_value(to_i64(self)?);
+                Ok(IsNull::No)
+            }
+        }
+
+        impl<T: Backing> ToSql<Text, Sqlite> for AsText<T> {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+                out.set_value(self.to_string());
+                Ok(IsNull::No)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::sizes::*, *};
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Ok(1536), to_i64(&ByteSize::of(1.5, KIBI_BYTE)));
+        assert_eq!(
+            Err(ParseError::ValueOverflow),
+            to_i64(&ByteSize::of(8, EXBI_BYTE))
+        );
+        assert_eq!(Ok(ByteSize::of(1, KIBI_BYTE)), from_i64(1024));
+        assert_eq!(Err(ParseError::InvalidValue), from_i64(-1));
+        assert_eq!(
+            Ok(ByteSize::of(8, EXBI_BYTE)),
+            from_text::<u64>("9223372036854775808")
+        );
+        assert_eq!(
+            Err(ParseError::ValueOverflow),
+            from_text::<u64>("18446744073709551616")
+        );
+        assert_eq!(
+            Ok(ByteSize::of_u128(16, EXBI_BYTE)),
+            from_text("18446744073709551616")
+        );
+        assert_eq!(Err(ParseError::InvalidValue), from_text::<u64>("1 KiB"));
+        assert_eq!(
+            "1536",
+            AsText::<u64>(ByteSize::of(1.5, KIBI_BYTE)).to_string()
+        );
+        assert_eq!(
+            "18446744073709551616",
+            AsText(ByteSize::of_u128(16, EXBI_BYTE)).to_string()
+        );
+    }
+
+    // sqlx's SQLite driver does its work on a thread of its own, waking the test when done
+    #[cfg(feature = "sqlx-sqlite")]
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        use std::{
+            sync::Arc,
+            task::{Context, Poll, Wake},
+            thread::{self, Thread},
+        };
+
+        struct Unpark(Thread);
+
+        impl Wake for Unpark {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark()
+            }
+        }
+
+        let waker = Arc::new(Unpark(thread::current())).into();
+        let mut cx = Context::from_waker(&waker);
+        let mut future = std::pin::pin!(future);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "sqlx-sqlite")]
+    fn sqlx_sqlite() {
+        use sqlx::{Connection, SqliteConnection};
+
+        block_on(async {
+            let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+            sqlx::query("CREATE TABLE files (size BIGINT NOT NULL, exact TEXT NOT NULL)")
+                .execute(&mut conn)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO files VALUES (?, ?)")
+                .bind(ByteSize::of(1.5, GIBI_BYTE))
+                .bind(AsText(ByteSize::of_u128(16, EXBI_BYTE)))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+
+            let (size, exact): (ByteSize, AsText<u128>) =
+                sqlx::query_as("SELECT size, exact FROM files")
+                    .fetch_one(&mut conn)
+                    .await
+                    .unwrap();
+            assert_eq!(ByteSize::of(1.5, GIBI_BYTE), size);
+            assert_eq!(ByteSize::of_u128(16, EXBI_BYTE), exact.0);
+
+            let bytes: i64 = sqlx::query_scalar("SELECT size FROM files")
+                .fetch_one(&mut conn)
+                .await
+                .unwrap();
+            assert_eq!(1610612736, bytes);
+
+            assert!(sqlx::query("INSERT INTO files VALUES (?, '0')")
+                .bind(ByteSize::of(8, EXBI_BYTE))
+                .execute(&mut conn)
+                .await
+                .is_err());
+            assert!(sqlx::query_scalar::<_, ByteSize>("SELECT -1")
+                .fetch_one(&mut conn)
+                .await
+                .is_err());
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "diesel-sqlite")]
+    fn diesel_sqlite() {
+        use diesel::{connection::SimpleConnection, prelude::*};
+
+        diesel::table! {
+            files (id) {
+                id -> Integer,
+                size -> BigInt,
+                exact -> Text,
+            }
+        }
+
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+        conn.batch_execute(
+            "CREATE TABLE files (id INTEGER PRIMARY KEY, size BIGINT NOT NULL, exact TEXT NOT NULL)",
+        )
+        .unwrap();
+        diesel::insert_into(files::table)
+            .values(&vec![
+                (
+                    files::size.eq(ByteSize::of(1.5, GIBI_BYTE)),
+                    files::exact.eq(AsText(ByteSize::of_u128(16, EXBI_BYTE))),
+                ),
+                (
+                    files::size.eq(ByteSize::of(512, KIBI_BYTE)),
+                    files::exact.eq(AsText(ByteSize::of_u128(0, BYTE))),
+                ),
+            ])
+            .execute(&mut conn)
+            .unwrap();
+
+        let large: Vec<(ByteSize, AsText<u128>)> = files::table
+            .select((files::size, files::exact))
+            .filter(files::size.gt(ByteSize::of(1, MEBI_BYTE)))
+            .load(&mut conn)
+            .unwrap();
+        assert_eq!(
+            vec![(
+                ByteSize::of(1.5, GIBI_BYTE),
+                AsText(ByteSize::of_u128(16, EXBI_BYTE))
+            )],
+            large
+        );
+
+        assert!(diesel::insert_into(files::table)
+            .values((
+                files::size.eq(ByteSize::of(8, EXBI_BYTE)),
+                files::exact.eq(AsText::<u64>(ByteSize::of(0, BYTE))),
+            ))
+            .execute(&mut conn)
+            .is_err());
+
+        conn.batch_execute("UPDATE files SET size = -1").unwrap();
+        assert!(files::table
+            .select(files::size)
+            .load::<ByteSize>(&mut conn)
+            .is_err());
+    }
+}
@@ -1,10 +1,12 @@
 use super::{
-    Int, Mode, ParseError,
+    ArrayString, Int, Mode, ParseError,
     UnitPrefix::{self, *},
 };
-use std::{
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
+use core::{
     cmp::{Ord, Ordering},
-    fmt,
+    fmt::{self, Write},
     str::FromStr,
 };
 
@@ -47,10 +49,10 @@ pub mod sizes {
         pub const TERA_BYTE: Unit = Unit::of(Tera, Byte);
         pub const PETA_BYTE: Unit = Unit::of(Peta, Byte);
         pub const EXA_BYTE : Unit = Unit::of(Exa , Byte);
-        #[cfg(feature = "u128")] pub const ZETTA_BIT : Unit = Unit::of(Zetta, Bit );
-        #[cfg(feature = "u128")] pub const YOTTA_BIT : Unit = Unit::of(Yotta, Bit );
-        #[cfg(feature = "u128")] pub const ZETTA_BYTE: Unit = Unit::of(Zetta, Byte);
-        #[cfg(feature = "u128")] pub const YOTTA_BYTE: Unit = Unit::of(Yotta, Byte);
+        pub const ZETTA_BIT : Unit = Unit::of(Zetta, Bit );
+        pub const YOTTA_BIT : Unit = Unit::of(Yotta, Bit );
+        pub const ZETTA_BYTE: Unit = Unit::of(Zetta, Byte);
+        pub const YOTTA_BYTE: Unit = Unit::of(Yotta, Byte);
     }
 
     #[rustfmt::skip]
@@ -68,10 +70,10 @@ pub mod sizes {
         pub const TEBI_BYTE: Unit = Unit::of(Tebi, Byte);
         pub const PEBI_BYTE: Unit = Unit::of(Pebi, Byte);
         pub const EXBI_BYTE: Unit = Unit::of(Exbi, Byte);
-        #[cfg(feature = "u128")] pub const ZEBI_BIT : Unit = Unit::of(Zebi, Bit );
-        #[cfg(feature = "u128")] pub const YOBI_BIT : Unit = Unit::of(Yobi, Bit );
-        #[cfg(feature = "u128")] pub const ZEBI_BYTE: Unit = Unit::of(Zebi, Byte);
-        #[cfg(feature = "u128")] pub const YOBI_BYTE: Unit = Unit::of(Yobi, Byte);
+        pub const ZEBI_BIT : Unit = Unit::of(Zebi, Bit );
+        pub const YOBI_BIT : Unit = Unit::of(Yobi, Bit );
+        pub const ZEBI_BYTE: Unit = Unit::of(Zebi, Byte);
+        pub const YOBI_BYTE: Unit = Unit::of(Yobi, Byte);
     }
 
     pub use {binary::*, decimal::*, noprefix::*};
@@ -82,10 +84,6 @@ pub mod sizes {
             BIT,
             KILO_BIT, KIBI_BIT, MEGA_BIT, MEBI_BIT, GIGA_BIT, GIBI_BIT,
             TERA_BIT, TEBI_BIT, PETA_BIT, PEBI_BIT, EXA_BIT, EXBI_BIT,
-        };
-
-        #[cfg(feature = "u128")]
-        pub use super::{
             ZETTA_BIT, ZEBI_BIT, YOTTA_BIT, YOBI_BIT,
         };
     }
@@ -96,10 +94,6 @@ pub mod sizes {
             BYTE,
             KILO_BYTE, KIBI_BYTE, MEGA_BYTE, MEBI_BYTE, GIGA_BYTE, GIBI_BYTE,
             TERA_BYTE, TEBI_BYTE, PETA_BYTE, PEBI_BYTE, EXA_BYTE, EXBI_BYTE,
-        };
-
-        #[cfg(feature = "u128")]
-        pub use super::{
             ZETTA_BYTE, ZEBI_BYTE, YOTTA_BYTE, YOBI_BYTE,
         };
     }
@@ -110,10 +104,6 @@ pub mod sizes {
             KILO_BIT, KIBI_BIT, KILO_BYTE, KIBI_BYTE, MEGA_BIT, MEBI_BIT, MEGA_BYTE, MEBI_BYTE,
             GIGA_BIT, GIBI_BIT, GIGA_BYTE, GIBI_BYTE, TERA_BIT, TEBI_BIT, TERA_BYTE, TEBI_BYTE,
             PETA_BIT, PEBI_BIT, PETA_BYTE, PEBI_BYTE, EXA_BIT, EXBI_BIT, EXA_BYTE, EXBI_BYTE,
-        };
-
-        #[cfg(feature = "u128")]
-        pub use super::{
             ZETTA_BIT, ZEBI_BIT, ZETTA_BYTE, ZEBI_BYTE,
             YOTTA_BIT, YOBI_BIT, YOTTA_BYTE, YOBI_BYTE,
         };
@@ -126,10 +116,6 @@ pub mod sizes {
             KILO_BIT, KIBI_BIT, KILO_BYTE, KIBI_BYTE, MEGA_BIT, MEBI_BIT, MEGA_BYTE, MEBI_BYTE,
             GIGA_BIT, GIBI_BIT, GIGA_BYTE, GIBI_BYTE, TERA_BIT, TEBI_BIT, TERA_BYTE, TEBI_BYTE,
             PETA_BIT, PEBI_BIT, PETA_BYTE, PEBI_BYTE, EXA_BIT, EXBI_BIT, EXA_BYTE, EXBI_BYTE,
-        };
-
-        #[cfg(feature = "u128")]
-        pub use super::{
             ZETTA_BIT, ZEBI_BIT, ZETTA_BYTE, ZEBI_BYTE,
             YOTTA_BIT, YOBI_BIT, YOTTA_BYTE, YOBI_BYTE,
         };
@@ -138,92 +124,50 @@ pub mod sizes {
     pub const NOPREFIX: [Unit; 2] = [BIT, BYTE];
 
     #[rustfmt::skip]
-    pub const DECIMAL: [Unit; {
-        #[cfg(feature = "u128")] { 16 }
-        #[cfg(not(feature = "u128"))] { 12 }
-    }] = [
+    pub const DECIMAL: [Unit; 16] = [
         KILO_BIT, KILO_BYTE, MEGA_BIT, MEGA_BYTE, GIGA_BIT, GIGA_BYTE,
         TERA_BIT, TERA_BYTE, PETA_BIT, PETA_BYTE, EXA_BIT, EXA_BYTE,
-        #[cfg(feature = "u128")] ZETTA_BIT,
-        #[cfg(feature = "u128")] ZETTA_BYTE,
-        #[cfg(feature = "u128")] YOTTA_BIT,
-        #[cfg(feature = "u128")] YOTTA_BYTE,
+        ZETTA_BIT, ZETTA_BYTE, YOTTA_BIT, YOTTA_BYTE,
     ];
 
     #[rustfmt::skip]
-    pub const BINARY: [Unit; {
-        #[cfg(feature = "u128")] { 16 }
-        #[cfg(not(feature = "u128"))] { 12 }
-    }] = [
+    pub const BINARY: [Unit; 16] = [
         KIBI_BIT, KIBI_BYTE, MEBI_BIT, MEBI_BYTE, GIBI_BIT, GIBI_BYTE,
         TEBI_BIT, TEBI_BYTE, PEBI_BIT, PEBI_BYTE, EXBI_BIT, EXBI_BYTE,
-        #[cfg(feature = "u128")] ZEBI_BIT,
-        #[cfg(feature = "u128")] ZEBI_BYTE,
-        #[cfg(feature = "u128")] YOBI_BIT,
-        #[cfg(feature = "u128")] YOBI_BYTE,
+        ZEBI_BIT, ZEBI_BYTE, YOBI_BIT, YOBI_BYTE,
     ];
 
     #[rustfmt::skip]
-    pub const BITS: [Unit; {
-        #[cfg(feature = "u128")] { 17 }
-        #[cfg(not(feature = "u128"))] { 13 }
-    }] = [
+    pub const BITS: [Unit; 17] = [
         BIT, KILO_BIT, KIBI_BIT, MEGA_BIT, MEBI_BIT, GIGA_BIT, GIBI_BIT,
         TERA_BIT, TEBI_BIT, PETA_BIT, PEBI_BIT, EXA_BIT, EXBI_BIT,
-        #[cfg(feature = "u128")] ZETTA_BIT,
-        #[cfg(feature = "u128")] ZEBI_BIT,
-        #[cfg(feature = "u128")] YOTTA_BIT,
-        #[cfg(feature = "u128")] YOBI_BIT,
+        ZETTA_BIT, ZEBI_BIT, YOTTA_BIT, YOBI_BIT,
     ];
 
     #[rustfmt::skip]
-    pub const BYTES: [Unit; {
-        #[cfg(feature = "u128")] { 17 }
-        #[cfg(not(feature = "u128"))] { 13 }
-    }] = [
+    pub const BYTES: [Unit; 17] = [
         BYTE, KILO_BYTE, KIBI_BYTE, MEGA_BYTE, MEBI_BYTE, GIGA_BYTE, GIBI_BYTE,
         TERA_BYTE, TEBI_BYTE, PETA_BYTE, PEBI_BYTE, EXA_BYTE, EXBI_BYTE,
-        #[cfg(feature = "u128")] ZETTA_BYTE,
-        #[cfg(feature = "u128")] ZEBI_BYTE,
-        #[cfg(feature = "u128")] YOTTA_BYTE,
-        #[cfg(feature = "u128")] YOBI_BYTE,
+        ZETTA_BYTE, ZEBI_BYTE, YOTTA_BYTE, YOBI_BYTE,
     ];
 
     #[rustfmt::skip]
-    pub const PREFIXED: [Unit; {
-        #[cfg(feature = "u128")] { 32 }
-        #[cfg(not(feature = "u128"))] { 24 }
-    }] = [
+    pub const PREFIXED: [Unit; 32] = [
         KILO_BIT, KIBI_BIT, KILO_BYTE, KIBI_BYTE, MEGA_BIT, MEBI_BIT, MEGA_BYTE, MEBI_BYTE,
         GIGA_BIT, GIBI_BIT, GIGA_BYTE, GIBI_BYTE, TERA_BIT, TEBI_BIT, TERA_BYTE, TEBI_BYTE,
         PETA_BIT, PEBI_BIT, PETA_BYTE, PEBI_BYTE, EXA_BIT, EXBI_BIT, EXA_BYTE, EXBI_BYTE,
-        #[cfg(feature = "u128")] ZETTA_BIT,
-        #[cfg(feature = "u128")] ZEBI_BIT,
-        #[cfg(feature = "u128")] ZETTA_BYTE,
-        #[cfg(feature = "u128")] ZEBI_BYTE,
-        #[cfg(feature = "u128")] YOTTA_BIT,
-        #[cfg(feature = "u128")] YOBI_BIT,
-        #[cfg(feature = "u128")] YOTTA_BYTE,
-        #[cfg(feature = "u128")] YOBI_BYTE,
+        ZETTA_BIT, ZEBI_BIT, ZETTA_BYTE, ZEBI_BYTE,
+        YOTTA_BIT, YOBI_BIT, YOTTA_BYTE, YOBI_BYTE,
     ];
 
     #[rustfmt::skip]
-    pub const ALL: [Unit; {
-        #[cfg(feature = "u128")] { 34 }
-        #[cfg(not(feature = "u128"))] { 26 }
-    }] = [
+    pub const ALL: [Unit; 34] = [
         BIT, BYTE,
         KILO_BIT, KIBI_BIT, KILO_BYTE, KIBI_BYTE, MEGA_BIT, MEBI_BIT, MEGA_BYTE, MEBI_BYTE,
         GIGA_BIT, GIBI_BIT, GIGA_BYTE, GIBI_BYTE, TERA_BIT, TEBI_BIT, TERA_BYTE, TEBI_BYTE,
         PETA_BIT, PEBI_BIT, PETA_BYTE, PEBI_BYTE, EXA_BIT, EXBI_BIT, EXA_BYTE, EXBI_BYTE,
-        #[cfg(feature = "u128")] ZETTA_BIT,
-        #[cfg(feature = "u128")] ZEBI_BIT,
-        #[cfg(feature = "u128")] ZETTA_BYTE,
-        #[cfg(feature = "u128")] ZEBI_BYTE,
-        #[cfg(feature = "u128")] YOTTA_BIT,
-        #[cfg(feature = "u128")] YOBI_BIT,
-        #[cfg(feature = "u128")] YOTTA_BYTE,
-        #[cfg(feature = "u128")] YOBI_BYTE,
+        ZETTA_BIT, ZEBI_BIT, ZETTA_BYTE, ZEBI_BYTE,
+        YOTTA_BIT, YOBI_BIT, YOTTA_BYTE, YOBI_BYTE,
     ];
 }
 
@@ -294,11 +238,9 @@ impl FromStr for SizeVariant {
             "" => Err(ParseError::EmptyInput),
             "b" => Ok(Bit),
             "B" => Ok(Byte),
-            s => match s.to_lowercase().as_str() {
-                "bit" | "bits" => Ok(Bit),
-                "byte" | "bytes" => Ok(Byte),
-                _ => Err(ParseError::InvalidSizeVariant),
-            },
+            s if ["bit", "bits"].iter().any(|v| v.eq_ignore_ascii_case(s)) => Ok(Bit),
+            s if ["byte", "bytes"].iter().any(|v| v.eq_ignore_ascii_case(s)) => Ok(Byte),
+            _ => Err(ParseError::InvalidSizeVariant),
         }
     }
 }
@@ -427,11 +369,17 @@ impl Unit {
         )
     }
 
+    #[cfg(feature = "alloc")]
     pub fn symbol(&self) -> String {
         let (prefix, size_variant) = self.symbols();
         format!("{}{}", prefix, size_variant)
     }
 
+    /// Same as [`Unit::symbol`], without allocating
+    pub fn symbol_inline(&self) -> ArrayString<16> {
+        inline(self.symbols())
+    }
+
     pub const fn symbols_long(
         &self,
         plural: bool,
@@ -447,11 +395,17 @@ impl Unit {
         )
     }
 
+    #[cfg(feature = "alloc")]
     pub fn symbol_long(&self, plural: bool, multi_caps: bool) -> String {
         let (prefix, size_variant) = self.symbols_long(plural, multi_caps);
         format!("{}{}", prefix, size_variant)
     }
 
+    /// Same as [`Unit::symbol_long`], without allocating
+    pub fn symbol_long_inline(&self, plural: bool, multi_caps: bool) -> ArrayString<16> {
+        inline(self.symbols_long(plural, multi_caps))
+    }
+
     // 'b', 'B', 'K', 'M', 'G', 'T', 'P', 'E', 'Z', 'Y'
     pub const fn symbol_condensed(&self) -> &'static str {
         match self.0 {
@@ -470,10 +424,128 @@ impl Unit {
         )
     }
 
+    #[cfg(feature = "alloc")]
     pub fn symbol_initials(&self) -> String {
         let (prefix, size_variant) = self.symbols_initials();
         format!("{}{}", prefix, size_variant)
     }
+
+    /// Same as [`Unit::symbol_initials`], without allocating
+    pub fn symbol_initials_inline(&self) -> ArrayString<16> {
+        inline(self.symbols_initials())
+    }
+
+    // `FromStr`, limited to symbols so it runs in const contexts
+    pub(crate) const fn from_symbol(s: &[u8]) -> Result<Self, ParseError> {
+        let (prefix, size_variant) = match s.split_last() {
+            Some((b'b', prefix)) => (prefix, Bit),
+            Some((b'B', prefix)) => (prefix, Byte),
+            Some(_) => return Err(ParseError::InvalidSizeVariant),
+            None => return Err(ParseError::EmptyInput),
+        };
+        if prefix.is_empty() {
+            return Ok(Self(None, size_variant));
+        }
+        if let [b'k'] = prefix {
+            return Ok(Self::of(Kilo, size_variant));
+        }
+        let mut index = 0;
+        while index < UnitPrefix::ALL.len() {
+            let symbol = UnitPrefix::ALL[index].symbol().as_bytes();
+            if eq_bytes(prefix, symbol) {
+                return Ok(Self::of(UnitPrefix::ALL[index], size_variant));
+            }
+            index += 1;
+        }
+        Err(ParseError::InvalidPrefix)
+    }
+}
+
+impl Unit {
+    /// Parses any unit the formatter writes out, e.g. with [`Format::LowerCaps`] or [`Format::Condensed`]
+    ///
+    /// Those can drop what tells units apart, so `mode` should be the one the size was
+    /// formatted with, as [`ByteSize::repr`] only ever picks units of that mode:
+    /// * [`Mode::Bits`] says whether a symbol in a single case (`"kb"`, `"KIB"`, `"b"`) or
+    ///   one without a size variant (`"K"`) is in bits or bytes
+    /// * [`Mode::Decimal`] says whether an initial without the `i` (`"K"`, `"KB"`) is decimal or binary
+    ///
+    /// Symbols in mixed case (`"Kb"`, `"kB"`) keep the size variant they're written with,
+    /// and long names are read as written.
+    ///
+    /// ```
+    /// use xbytes::{prelude::*, Unit};
+    ///
+    /// assert_eq!(Ok(KIBI_BYTE), Unit::parse_repr("kib", Mode::Default));
+    /// assert_eq!(Ok(KIBI_BIT), Unit::parse_repr("kib", Mode::Bits));
+    /// assert_eq!(Ok(KILO_BYTE), Unit::parse_repr("K", Mode::Decimal));
+    /// assert_eq!(Ok(KIBI_BYTE), Unit::parse_repr("Kibibytes", Mode::Bits));
+    /// ```
+    ///
+    /// [`Format::LowerCaps`]: crate::Format::LowerCaps
+    /// [`Format::Condensed`]: crate::Format::Condensed
+    /// [`ByteSize::repr`]: crate::ByteSize::repr
+    pub fn parse_repr(s: &str, mode: Mode) -> Result<Self, ParseError> {
+        let bytes = s.as_bytes();
+        if ["bit", "bits", "byte", "bytes"].iter().any(|variant| {
+            bytes.len() >= variant.len()
+                && bytes[bytes.len() - variant.len()..].eq_ignore_ascii_case(variant.as_bytes())
+        }) {
+            return s.parse();
+        }
+        let single_case =
+            !bytes.iter().any(u8::is_ascii_lowercase) || !bytes.iter().any(u8::is_ascii_uppercase);
+        let by_mode = if mode.contains(Mode::Bits) { Bit } else { Byte };
+        let (prefix, size_variant) = match bytes.split_last() {
+            Some((b'B', prefix)) if !single_case => (prefix, Byte),
+            Some((b'b', prefix)) if !single_case => (prefix, Bit),
+            Some((b'b' | b'B', prefix)) => (prefix, by_mode),
+            Some(_) => (bytes, by_mode),
+            None => return Err(ParseError::EmptyInput),
+        };
+        let initial = |prefixes: &'static [UnitPrefix], initial: &u8| {
+            prefixes
+                .iter()
+                .find(|prefix| prefix.symbol().as_bytes()[0].eq_ignore_ascii_case(initial))
+        };
+        let prefix = match prefix {
+            [] => return Ok(Self(None, size_variant)),
+            [letter] if mode.contains(Mode::Decimal) => initial(&UnitPrefix::DECIMAL, letter),
+            [letter] | [letter, b'i' | b'I'] => initial(&UnitPrefix::BINARY, letter),
+            _ => None,
+        };
+        match prefix {
+            Some(prefix) => Ok(Self::of(*prefix, size_variant)),
+            None => Err(ParseError::InvalidPrefix),
+        }
+    }
+}
+
+// the longest symbol, "Yottabytes", takes 10 bytes so this never runs out of room
+fn inline((prefix, size_variant): (&str, &str)) -> ArrayString<16> {
+    let mut symbol = ArrayString::new();
+    let _ = symbol.write_str(prefix);
+    let _ = symbol.write_str(size_variant);
+    symbol
+}
+
+// `==` on byte slices isn't const, and neither is `eq_ignore_ascii_case` before 1.89
+const fn eq_bytes(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut index = 0;
+    while index < a.len() {
+        #[cfg(not(feature = "case-insensitive"))]
+        let (x, y) = (a[index], b[index]);
+        #[cfg(feature = "case-insensitive")]
+        let (x, y) = (a[index].to_ascii_lowercase(), b[index].to_ascii_lowercase());
+        if x != y {
+            return false;
+        }
+        index += 1;
+    }
+    true
 }
 
 impl Ord for Unit {
@@ -492,18 +564,18 @@ impl PartialOrd for Unit {
 
 impl fmt::Display for Unit {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let unit = if f.sign_plus() {
-            self.symbol_long(!f.alternate(), true)
+        let (prefix, size_variant) = if f.sign_plus() {
+            self.symbols_long(!f.alternate(), true)
         } else if f.sign_minus() {
             if f.alternate() {
-                self.symbol_condensed().to_string()
+                (self.symbol_condensed(), "")
             } else {
-                self.symbol_initials()
+                self.symbols_initials()
             }
         } else {
-            self.symbol()
+            self.symbols()
         };
-        write!(f, "{}", unit)
+        write!(f, "{}{}", prefix, size_variant)
     }
 }
 
@@ -514,7 +586,7 @@ impl FromStr for Unit {
         if s.is_empty() {
             Err(ParseError::EmptyInput)
         } else {
-            let index = s.rfind(|c| matches!(c, 'b' | 'B')).unwrap_or(0);
+            let index = s.rfind(['b', 'B']).unwrap_or(0);
             let (prefix, size_variant) = s.split_at(index);
             let size_variant = size_variant.parse::<SizeVariant>()?;
             #[rustfmt::skip]
@@ -554,6 +626,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::nonminimal_bool)]
     fn size_variant_cmp() {
         assert!(Bit < Byte && Byte > Bit);
     }
@@ -660,6 +733,7 @@ mod tests {
 
     #[test]
     #[rustfmt::skip]
+    #[allow(clippy::nonminimal_bool)]
     fn unit_cmp() {
         assert!(KILO_BIT  < KIBI_BIT  && KIBI_BIT  > KILO_BIT );
         assert!(KIBI_BIT  < KILO_BYTE && KILO_BYTE > KIBI_BIT );
@@ -685,14 +759,14 @@ mod tests {
         assert!(EXBI_BIT  < EXA_BYTE  && EXA_BYTE  > EXBI_BIT );
         assert!(EXA_BYTE  < EXBI_BYTE && EXBI_BYTE > EXA_BYTE );
 
-        #[cfg(feature = "u128")] assert!(EXBI_BYTE  < ZETTA_BIT  && ZETTA_BIT  > EXBI_BYTE );
-        #[cfg(feature = "u128")] assert!(ZETTA_BIT  < ZEBI_BIT   && ZEBI_BIT   > ZETTA_BIT );
-        #[cfg(feature = "u128")] assert!(ZEBI_BIT   < ZETTA_BYTE && ZETTA_BYTE > ZEBI_BIT  );
-        #[cfg(feature = "u128")] assert!(ZETTA_BYTE < ZEBI_BYTE  && ZEBI_BYTE  > ZETTA_BYTE);
-        #[cfg(feature = "u128")] assert!(ZEBI_BYTE  < YOTTA_BIT  && YOTTA_BIT  > ZEBI_BYTE );
-        #[cfg(feature = "u128")] assert!(YOTTA_BIT  < YOBI_BIT   && YOBI_BIT   > YOTTA_BIT );
-        #[cfg(feature = "u128")] assert!(YOBI_BIT   < YOTTA_BYTE && YOTTA_BYTE > YOBI_BIT  );
-        #[cfg(feature = "u128")] assert!(YOTTA_BYTE < YOBI_BYTE  && YOBI_BYTE  > YOTTA_BYTE);
+        assert!(EXBI_BYTE  < ZETTA_BIT  && ZETTA_BIT  > EXBI_BYTE );
+        assert!(ZETTA_BIT  < ZEBI_BIT   && ZEBI_BIT   > ZETTA_BIT );
+        assert!(ZEBI_BIT   < ZETTA_BYTE && ZETTA_BYTE > ZEBI_BIT  );
+        assert!(ZETTA_BYTE < ZEBI_BYTE  && ZEBI_BYTE  > ZETTA_BYTE);
+        assert!(ZEBI_BYTE  < YOTTA_BIT  && YOTTA_BIT  > ZEBI_BYTE );
+        assert!(YOTTA_BIT  < YOBI_BIT   && YOBI_BIT   > YOTTA_BIT );
+        assert!(YOBI_BIT   < YOTTA_BYTE && YOTTA_BYTE > YOBI_BIT  );
+        assert!(YOTTA_BYTE < YOBI_BYTE  && YOBI_BYTE  > YOTTA_BYTE);
     }
 
     #[test]
@@ -735,8 +809,8 @@ mod tests {
         assert_eq!(TERA_BIT, Unit::of(Tera, Bit));
         assert_eq!(PETA_BIT, Unit::of(Peta, Bit));
         assert_eq!(EXA_BIT , Unit::of(Exa , Bit));
-        #[cfg(feature = "u128")] assert_eq!(ZETTA_BIT, Unit::of(Zetta, Bit));
-        #[cfg(feature = "u128")] assert_eq!(YOTTA_BIT, Unit::of(Yotta, Bit));
+        assert_eq!(ZETTA_BIT, Unit::of(Zetta, Bit));
+        assert_eq!(YOTTA_BIT, Unit::of(Yotta, Bit));
         // --
         assert_eq!(KILO_BYTE, Unit::of(Kilo, Byte));
         assert_eq!(MEGA_BYTE, Unit::of(Mega, Byte));
@@ -744,8 +818,8 @@ mod tests {
         assert_eq!(TERA_BYTE, Unit::of(Tera, Byte));
         assert_eq!(PETA_BYTE, Unit::of(Peta, Byte));
         assert_eq!(EXA_BYTE , Unit::of(Exa , Byte));
-        #[cfg(feature = "u128")] assert_eq!(ZETTA_BYTE, Unit::of(Zetta, Byte));
-        #[cfg(feature = "u128")] assert_eq!(YOTTA_BYTE, Unit::of(Yotta, Byte));
+        assert_eq!(ZETTA_BYTE, Unit::of(Zetta, Byte));
+        assert_eq!(YOTTA_BYTE, Unit::of(Yotta, Byte));
     }
 
     #[test]
@@ -756,9 +830,7 @@ mod tests {
         assert_eq!(TEBI_BIT, Unit::of(Tebi, Bit));
         assert_eq!(PEBI_BIT, Unit::of(Pebi, Bit));
         assert_eq!(EXBI_BIT, Unit::of(Exbi, Bit));
-        #[cfg(feature = "u128")]
         assert_eq!(ZEBI_BIT, Unit::of(Zebi, Bit));
-        #[cfg(feature = "u128")]
         assert_eq!(YOBI_BIT, Unit::of(Yobi, Bit));
         // --
         assert_eq!(KIBI_BYTE, Unit::of(Kibi, Byte));
@@ -767,9 +839,7 @@ mod tests {
         assert_eq!(TEBI_BYTE, Unit::of(Tebi, Byte));
         assert_eq!(PEBI_BYTE, Unit::of(Pebi, Byte));
         assert_eq!(EXBI_BYTE, Unit::of(Exbi, Byte));
-        #[cfg(feature = "u128")]
         assert_eq!(ZEBI_BYTE, Unit::of(Zebi, Byte));
-        #[cfg(feature = "u128")]
         assert_eq!(YOBI_BYTE, Unit::of(Yobi, Byte));
     }
 
@@ -802,10 +872,10 @@ mod tests {
             (TERA_BIT,   4), (TERA_BYTE,   4),
             (PETA_BIT,   5), (PETA_BYTE,   5),
             (EXA_BIT ,   6), (EXA_BYTE ,   6),
-            #[cfg(feature = "u128")] (ZETTA_BIT ,  7),
-            #[cfg(feature = "u128")] (ZETTA_BYTE,  7),
-            #[cfg(feature = "u128")] (YOTTA_BIT ,  8),
-            #[cfg(feature = "u128")] (YOTTA_BYTE,  8),
+            (ZETTA_BIT ,  7),
+            (ZETTA_BYTE,  7),
+            (YOTTA_BIT ,  8),
+            (YOTTA_BYTE,  8),
             // --
             (KIBI_BIT,   1), (KIBI_BYTE,   1),
             (MEBI_BIT,   2), (MEBI_BYTE,   2),
@@ -813,10 +883,10 @@ mod tests {
             (TEBI_BIT,   4), (TEBI_BYTE,   4),
             (PEBI_BIT,   5), (PEBI_BYTE,   5),
             (EXBI_BIT,   6), (EXBI_BYTE,   6),
-            #[cfg(feature = "u128")] (ZEBI_BIT ,   7),
-            #[cfg(feature = "u128")] (ZEBI_BYTE,   7),
-            #[cfg(feature = "u128")] (YOBI_BIT ,   8),
-            #[cfg(feature = "u128")] (YOBI_BYTE,   8),
+            (ZEBI_BIT ,   7),
+            (ZEBI_BYTE,   7),
+            (YOBI_BIT ,   8),
+            (YOBI_BYTE,   8),
         ];
 
         for (unit, index) in map.iter() {
@@ -839,8 +909,8 @@ mod tests {
         assert_eq!(TERA_BIT, TEBI_BIT.decimal());
         assert_eq!(PETA_BIT, PEBI_BIT.decimal());
         assert_eq!(EXA_BIT , EXBI_BIT.decimal());
-        #[cfg(feature = "u128")] assert_eq!(ZETTA_BIT, ZEBI_BIT.decimal());
-        #[cfg(feature = "u128")] assert_eq!(YOTTA_BIT, YOBI_BIT.decimal());
+        assert_eq!(ZETTA_BIT, ZEBI_BIT.decimal());
+        assert_eq!(YOTTA_BIT, YOBI_BIT.decimal());
         // --
         assert_eq!(KILO_BYTE, KIBI_BYTE.decimal());
         assert_eq!(MEGA_BYTE, MEBI_BYTE.decimal());
@@ -848,8 +918,8 @@ mod tests {
         assert_eq!(TERA_BYTE, TEBI_BYTE.decimal());
         assert_eq!(PETA_BYTE, PEBI_BYTE.decimal());
         assert_eq!(EXA_BYTE , EXBI_BYTE.decimal());
-        #[cfg(feature = "u128")] assert_eq!(ZETTA_BYTE, ZEBI_BYTE.decimal());
-        #[cfg(feature = "u128")] assert_eq!(YOTTA_BYTE, YOBI_BYTE.decimal());
+        assert_eq!(ZETTA_BYTE, ZEBI_BYTE.decimal());
+        assert_eq!(YOTTA_BYTE, YOBI_BYTE.decimal());
     }
 
     #[test]
@@ -861,8 +931,8 @@ mod tests {
         assert_eq!(TEBI_BIT, TERA_BIT.binary());
         assert_eq!(PEBI_BIT, PETA_BIT.binary());
         assert_eq!(EXBI_BIT, EXA_BIT .binary());
-        #[cfg(feature = "u128")] assert_eq!(ZEBI_BIT, ZETTA_BIT.binary());
-        #[cfg(feature = "u128")] assert_eq!(YOBI_BIT, YOTTA_BIT.binary());
+        assert_eq!(ZEBI_BIT, ZETTA_BIT.binary());
+        assert_eq!(YOBI_BIT, YOTTA_BIT.binary());
         // --
         assert_eq!(KIBI_BYTE, KILO_BYTE.binary());
         assert_eq!(MEBI_BYTE, MEGA_BYTE.binary());
@@ -870,8 +940,8 @@ mod tests {
         assert_eq!(TEBI_BYTE, TERA_BYTE.binary());
         assert_eq!(PEBI_BYTE, PETA_BYTE.binary());
         assert_eq!(EXBI_BYTE, EXA_BYTE .binary());
-        #[cfg(feature = "u128")] assert_eq!(ZEBI_BYTE, ZETTA_BYTE.binary());
-        #[cfg(feature = "u128")] assert_eq!(YOBI_BYTE, YOTTA_BYTE.binary());
+        assert_eq!(ZEBI_BYTE, ZETTA_BYTE.binary());
+        assert_eq!(YOBI_BYTE, YOTTA_BYTE.binary());
     }
 
     #[test]
@@ -883,8 +953,8 @@ mod tests {
         assert_eq!(TERA_BIT, TERA_BIT.bit());
         assert_eq!(PETA_BIT, PETA_BIT.bit());
         assert_eq!(EXA_BIT , EXA_BIT .bit());
-        #[cfg(feature = "u128")] assert_eq!(ZETTA_BIT, ZETTA_BIT.bit());
-        #[cfg(feature = "u128")] assert_eq!(YOTTA_BIT, YOTTA_BIT.bit());
+        assert_eq!(ZETTA_BIT, ZETTA_BIT.bit());
+        assert_eq!(YOTTA_BIT, YOTTA_BIT.bit());
         // --
         assert_eq!(KILO_BIT, KILO_BYTE.bit());
         assert_eq!(MEGA_BIT, MEGA_BYTE.bit());
@@ -892,8 +962,8 @@ mod tests {
         assert_eq!(TERA_BIT, TERA_BYTE.bit());
         assert_eq!(PETA_BIT, PETA_BYTE.bit());
         assert_eq!(EXA_BIT , EXA_BYTE .bit());
-        #[cfg(feature = "u128")] assert_eq!(ZETTA_BIT, ZETTA_BYTE.bit());
-        #[cfg(feature = "u128")] assert_eq!(YOTTA_BIT, YOTTA_BYTE.bit());
+        assert_eq!(ZETTA_BIT, ZETTA_BYTE.bit());
+        assert_eq!(YOTTA_BIT, YOTTA_BYTE.bit());
     }
 
     #[test]
@@ -904,9 +974,7 @@ mod tests {
         assert_eq!(TEBI_BYTE, TEBI_BIT.byte());
         assert_eq!(PEBI_BYTE, PEBI_BIT.byte());
         assert_eq!(EXBI_BYTE, EXBI_BIT.byte());
-        #[cfg(feature = "u128")]
         assert_eq!(ZEBI_BYTE, ZEBI_BIT.byte());
-        #[cfg(feature = "u128")]
         assert_eq!(YOBI_BYTE, YOBI_BIT.byte());
         // --
         assert_eq!(KIBI_BYTE, KIBI_BYTE.byte());
@@ -915,9 +983,7 @@ mod tests {
         assert_eq!(TEBI_BYTE, TEBI_BYTE.byte());
         assert_eq!(PEBI_BYTE, PEBI_BYTE.byte());
         assert_eq!(EXBI_BYTE, EXBI_BYTE.byte());
-        #[cfg(feature = "u128")]
         assert_eq!(ZEBI_BYTE, ZEBI_BYTE.byte());
-        #[cfg(feature = "u128")]
         assert_eq!(YOBI_BYTE, YOBI_BYTE.byte());
     }
 
@@ -993,20 +1059,20 @@ mod tests {
             (TERA_BIT, 1000000000000),              (TERA_BYTE, 8000000000000),
             (PETA_BIT, 1000000000000000),           (PETA_BYTE, 8000000000000000),
             (EXA_BIT , 1000000000000000000),        (EXA_BYTE , 8000000000000000000),
-            #[cfg(feature = "u128")] (ZETTA_BIT , 1000000000000000000000),
-            #[cfg(feature = "u128")] (YOTTA_BIT , 1000000000000000000000000),
-            #[cfg(feature = "u128")] (ZETTA_BYTE, 8000000000000000000000),
-            #[cfg(feature = "u128")] (YOTTA_BYTE, 8000000000000000000000000),
+            (ZETTA_BIT , 1000000000000000000000),
+            (YOTTA_BIT , 1000000000000000000000000),
+            (ZETTA_BYTE, 8000000000000000000000),
+            (YOTTA_BYTE, 8000000000000000000000000),
             (KIBI_BIT, 1024),                       (KIBI_BYTE, 8192),
             (MEBI_BIT, 1048576),                    (MEBI_BYTE, 8388608),
             (GIBI_BIT, 1073741824),                 (GIBI_BYTE, 8589934592),
             (TEBI_BIT, 1099511627776),              (TEBI_BYTE, 8796093022208),
             (PEBI_BIT, 1125899906842624),           (PEBI_BYTE, 9007199254740992),
             (EXBI_BIT, 1152921504606846976),        (EXBI_BYTE, 9223372036854775808),
-            #[cfg(feature = "u128")] (ZEBI_BIT , 1180591620717411303424),
-            #[cfg(feature = "u128")] (YOBI_BIT , 1208925819614629174706176),
-            #[cfg(feature = "u128")] (ZEBI_BYTE, 9444732965739290427392),
-            #[cfg(feature = "u128")] (YOBI_BYTE, 9671406556917033397649408),
+            (ZEBI_BIT , 1180591620717411303424),
+            (YOBI_BIT , 1208925819614629174706176),
+            (ZEBI_BYTE, 9444732965739290427392),
+            (YOBI_BYTE, 9671406556917033397649408),
         ];
 
         for (unit, value) in map.iter() {
@@ -1023,13 +1089,11 @@ mod tests {
     #[test]
     fn unit_min_max() {
         assert_eq!(Unit(None, Bit), Unit::MIN);
-        #[cfg(feature = "u128")]
         assert_eq!(Unit(Some(Yobi), Byte), Unit::MAX);
-        #[cfg(not(feature = "u128"))]
-        assert_eq!(Unit(Some(Exbi), Byte), Unit::MAX);
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn unit_format_and_display_symbol() {
         #[rustfmt::skip]
         let map = [
@@ -1056,10 +1120,10 @@ mod tests {
             (PETA_BYTE, "P", "PB", "PB" , "PetaByte" , "Petabyte" , "PetaBytes", "Petabytes"),
             (EXA_BIT  , "E", "Eb", "Eb" , "ExaBit"   , "Exabit"   , "ExaBits"  , "Exabits"  ),
             (EXA_BYTE , "E", "EB", "EB" , "ExaByte"  , "Exabyte"  , "ExaBytes" , "Exabytes" ),
-            #[cfg(feature = "u128")] (ZETTA_BIT , "Z", "Zb", "Zb" , "ZettaBit" , "Zettabit" , "ZettaBits" , "Zettabits" ),
-            #[cfg(feature = "u128")] (ZETTA_BYTE, "Z", "ZB", "ZB" , "ZettaByte", "Zettabyte", "ZettaBytes", "Zettabytes"),
-            #[cfg(feature = "u128")] (YOTTA_BIT , "Y", "Yb", "Yb" , "YottaBit" , "Yottabit" , "YottaBits" , "Yottabits" ),
-            #[cfg(feature = "u128")] (YOTTA_BYTE, "Y", "YB", "YB" , "YottaByte", "Yottabyte", "YottaBytes", "Yottabytes"),
+            (ZETTA_BIT , "Z", "Zb", "Zb" , "ZettaBit" , "Zettabit" , "ZettaBits" , "Zettabits" ),
+            (ZETTA_BYTE, "Z", "ZB", "ZB" , "ZettaByte", "Zettabyte", "ZettaBytes", "Zettabytes"),
+            (YOTTA_BIT , "Y", "Yb", "Yb" , "YottaBit" , "Yottabit" , "YottaBits" , "Yottabits" ),
+            (YOTTA_BYTE, "Y", "YB", "YB" , "YottaByte", "Yottabyte", "YottaBytes", "Yottabytes"),
             (KIBI_BIT , "K", "Kb", "Kib", "KibiBit"  , "Kibibit"  , "KibiBits" , "Kibibits" ),
             (KIBI_BYTE, "K", "KB", "KiB", "KibiByte" , "Kibibyte" , "KibiBytes", "Kibibytes"),
             (MEBI_BIT , "M", "Mb", "Mib", "MebiBit"  , "Mebibit"  , "MebiBits" , "Mebibits" ),
@@ -1072,10 +1136,10 @@ mod tests {
             (PEBI_BYTE, "P", "PB", "PiB", "PebiByte" , "Pebibyte" , "PebiBytes", "Pebibytes"),
             (EXBI_BIT , "E", "Eb", "Eib", "ExbiBit"  , "Exbibit"  , "ExbiBits" , "Exbibits" ),
             (EXBI_BYTE, "E", "EB", "EiB", "ExbiByte" , "Exbibyte" , "ExbiBytes", "Exbibytes"),
-            #[cfg(feature = "u128")] (ZEBI_BIT , "Z", "Zb", "Zib", "ZebiBit"  , "Zebibit"  , "ZebiBits"  , "Zebibits"  ),
-            #[cfg(feature = "u128")] (ZEBI_BYTE, "Z", "ZB", "ZiB", "ZebiByte" , "Zebibyte" , "ZebiBytes" , "Zebibytes" ),
-            #[cfg(feature = "u128")] (YOBI_BIT , "Y", "Yb", "Yib", "YobiBit"  , "Yobibit"  , "YobiBits"  , "Yobibits"  ),
-            #[cfg(feature = "u128")] (YOBI_BYTE, "Y", "YB", "YiB", "YobiByte" , "Yobibyte" , "YobiBytes" , "Yobibytes" ),
+            (ZEBI_BIT , "Z", "Zb", "Zib", "ZebiBit"  , "Zebibit"  , "ZebiBits"  , "Zebibits"  ),
+            (ZEBI_BYTE, "Z", "ZB", "ZiB", "ZebiByte" , "Zebibyte" , "ZebiBytes" , "Zebibytes" ),
+            (YOBI_BIT , "Y", "Yb", "Yib", "YobiBit"  , "Yobibit"  , "YobiBits"  , "Yobibits"  ),
+            (YOBI_BYTE, "Y", "YB", "YiB", "YobiByte" , "Yobibyte" , "YobiBytes" , "Yobibytes" ),
         ];
 
         for (
@@ -1138,6 +1202,15 @@ mod tests {
                 unit,
                 long_extra
             );
+            assert_eq!(*initials, unit.symbol_initials_inline());
+            assert_eq!(*normal, unit.symbol_inline());
+            assert_eq!(*long, unit.symbol_long_inline(false, true));
+            assert_eq!(*long_single_caps, unit.symbol_long_inline(false, false));
+            assert_eq!(*long_extra, unit.symbol_long_inline(true, true));
+            assert_eq!(
+                *long_extra_single_caps,
+                unit.symbol_long_inline(true, false)
+            );
         }
     }
 
@@ -1199,4 +1272,37 @@ mod tests {
         assert_eq!(Err(ParseError::InvalidSizeVariant), "m".parse::<Unit>());
         assert_eq!(Err(ParseError::InvalidPrefix), "m b".parse::<Unit>());
     }
+
+    #[test]
+    fn unit_parse_repr() {
+        let bits_decimal = Mode::Bits | Mode::Decimal;
+        #[rustfmt::skip]
+        let map = [
+            ("KiB", Mode::Bits, Ok(KIBI_BYTE)), ("Kb", Mode::Default, Ok(KIBI_BIT)),
+            ("kB", Mode::Bits, Ok(KIBI_BYTE)), ("kB", Mode::Decimal, Ok(KILO_BYTE)),
+            ("kib", Mode::Default, Ok(KIBI_BYTE)), ("kib", Mode::Bits, Ok(KIBI_BIT)),
+            ("MIB", Mode::Default, Ok(MEBI_BYTE)), ("MIB", Mode::Bits, Ok(MEBI_BIT)),
+            ("kb", Mode::Decimal, Ok(KILO_BYTE)), ("kb", bits_decimal, Ok(KILO_BIT)),
+            ("KB", Mode::Default, Ok(KIBI_BYTE)), ("KB", bits_decimal, Ok(KILO_BIT)),
+            ("b", Mode::Default, Ok(BYTE)), ("B", Mode::Bits, Ok(BIT)),
+            ("G", Mode::Default, Ok(GIBI_BYTE)), ("g", bits_decimal, Ok(GIGA_BIT)),
+            ("Kilobytes", Mode::Bits, Ok(KILO_BYTE)), ("MEBIBITS", Mode::Default, Ok(MEBI_BIT)),
+            ("bit", Mode::Default, Ok(BIT)),
+            ("", Mode::Default, Err(ParseError::EmptyInput)),
+            ("XB", Mode::Default, Err(ParseError::InvalidPrefix)),
+            ("KiiB", Mode::Default, Err(ParseError::InvalidPrefix)),
+            ("X", Mode::Default, Err(ParseError::InvalidPrefix)),
+        ];
+
+        for (value, mode, unit) in map.iter() {
+            assert_eq!(
+                *unit,
+                Unit::parse_repr(value, *mode),
+                "expected [{}] in {:?} to parse as {:?}",
+                value,
+                mode,
+                unit
+            );
+        }
+    }
 }
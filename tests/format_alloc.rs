@@ -0,0 +1,65 @@
+//! Formatting makes no heap allocations
+//!
+//! Lives in a binary of its own, so the counting allocator doesn't stand in for every other test's.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    fmt::Write,
+};
+use xbytes::{sizes, ArrayString, BitSize, ByteSize, Format, Mode};
+
+// counts the allocations made on each thread, the test harness allocates on its own
+struct CountingAlloc;
+
+std::thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAlloc = CountingAlloc;
+
+#[test]
+fn format_without_allocating() {
+    let mut text = ArrayString::<64>::new();
+    let allocations = ALLOCATIONS.with(|n| n.get());
+    for mode in 0..=Mode::all().bits() {
+        let mode = Mode::from_bits_truncate(mode);
+        for format in 0..=Format::all().bits() {
+            let format = Format::from_bits_truncate(format);
+            for value in [0, 1, 1536, 1234567, u64::MAX] {
+                for repr in [
+                    ByteSize::from_bytes(value).repr(mode),
+                    BitSize::from_bits(value).repr(mode),
+                ] {
+                    text.clear();
+                    repr.with(format).write_to(&mut text).unwrap();
+                    text.clear();
+                    write!(text, "{:+#.3}", repr.with(format)).unwrap();
+                }
+                text.clear();
+                let exact = ByteSize::from_bytes(value).repr_exact();
+                write!(text, "{}", exact.with(format)).unwrap();
+            }
+        }
+    }
+    for unit in sizes::ALL {
+        text.clear();
+        write!(text, "{} {:+} {:-#}", unit, unit, unit).unwrap();
+        unit.symbol_inline();
+        unit.symbol_long_inline(true, false);
+        unit.symbol_initials_inline();
+    }
+    assert_eq!(allocations, ALLOCATIONS.with(|n| n.get()));
+}